@@ -88,7 +88,7 @@ fn create_if_let(
         if let (#(#match_path(#pats),)*) = (#(#idents.0, )*) {
             #body
         } else {
-            panic! {#error_msg}
+            panic! {"{}: {}", chronobreak::clock::ClockError::MixedMockedAndActual, #error_msg}
         }
     };
     let constants_exprs = mapped.iter().map(|v| &v.1);
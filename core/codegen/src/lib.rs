@@ -16,6 +16,7 @@ mod apply_fn;
 mod chronobreak_attr;
 mod constants_fn;
 mod test_attr;
+mod timestamp;
 
 /// Enables the mock on an import or a group of imports.
 ///
@@ -54,7 +55,9 @@ pub fn chronobreak(args: TokenStream, tokens: TokenStream) -> TokenStream {
 /// Enables an (async) test function with a mocked clock.
 ///
 /// Async tests require [async-std](https://crates.io/crates/async-std) as a
-/// dependency.
+/// dependency. Scheduled async tests are instead run by
+/// `chronobreak::clock::block_on`, which lets the scheduled clock know when
+/// the test has no more runnable work.
 ///
 /// # Examples
 ///
@@ -75,6 +78,40 @@ pub fn chronobreak(args: TokenStream, tokens: TokenStream) -> TokenStream {
 /// fn test_with_frozen_clock() {
 ///     // [...]
 /// }
+///
+/// #[chronobreak::test(scheduled)]
+/// fn test_with_scheduled_clock() {
+///     // [...]
+/// }
+///
+/// #[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+/// fn test_with_system_time_start() {
+///     // [...]
+/// }
+///
+/// #[chronobreak::test(instant_start = "0s")]
+/// fn test_with_instants_starting_at_zero() {
+///     // [...]
+/// }
+///
+/// #[chronobreak::test(max_time = "1h", max_timed_waits = 1000)]
+/// fn test_with_limits() {
+///     // [...]
+/// }
+///
+/// // Prints the statistics of the clock to stderr if the test fails.
+/// #[chronobreak::test(print_stats)]
+/// fn test_with_statistics() {
+///     // [...]
+/// }
+///
+/// // Requires the `scenario` feature of chronobreak. The path is relative to
+/// // the directory of the crate's manifest.
+/// #[chronobreak::test(scenario = "tests/scenarios/failover.toml")]
+/// fn test_with_scenario(scenario: &mut Scenario) {
+///     scenario.hook("drop_connection", || { /* [...] */ });
+///     scenario.assertion("failed_over", || { /* [...] */ true });
+/// }
 /// ```
 #[proc_macro_attribute]
 #[proc_macro_error]
@@ -1,14 +1,33 @@
+#![allow(clippy::manual_unwrap_or_default)] // triggered by code generated by darling
+
+use super::timestamp;
+use chronobreak_globals::duration;
 use darling::FromMeta;
 use proc_macro::TokenStream;
 use proc_macro_error::*;
 use quote::quote;
 use std::convert::TryInto;
-use syn::{parse_quote, AttributeArgs, Item, ItemFn};
+use std::time::Duration;
+use syn::{parse_quote, AttributeArgs, FnArg, Item, ItemFn, LitStr, ReturnType};
 
 #[derive(FromMeta)]
 struct FnArgs {
     #[darling(default)]
     frozen: bool,
+    #[darling(default)]
+    scheduled: bool,
+    #[darling(default)]
+    start: Option<LitStr>,
+    #[darling(default)]
+    instant_start: Option<LitStr>,
+    #[darling(default)]
+    max_time: Option<LitStr>,
+    #[darling(default)]
+    max_timed_waits: Option<u64>,
+    #[darling(default)]
+    scenario: Option<LitStr>,
+    #[darling(default)]
+    print_stats: bool,
 }
 
 impl TryInto<FnArgs> for AttributeArgs {
@@ -29,27 +48,139 @@ pub fn derive(args: AttributeArgs, tokens: TokenStream) -> Result<TokenStream, T
 }
 
 fn derive_item_fn(args: &FnArgs, item_fn: &ItemFn) -> Item {
+    if args.frozen && args.scheduled {
+        abort! {item_fn.sig, "#[chronobreak::test] may not be both frozen and scheduled"};
+    }
+    let config = derive_config(args);
+    let attrs = &item_fn.attrs;
+    let vis = &item_fn.vis;
+    let stmts = &item_fn.block.stmts;
+    if let Some(scenario) = &args.scenario {
+        return derive_scenario_fn(scenario, config, item_fn);
+    }
+    if args.scheduled && item_fn.sig.asyncness.is_some() {
+        let mut sig = item_fn.sig.clone();
+        sig.asyncness = None;
+        return Item::Fn(parse_quote! {
+            #[test]
+            #(#attrs)*
+            #vis #sig {
+                use ::chronobreak::clock;
+                let _clock = clock::mock_with(#config);
+                clock::block_on(async move { #(#stmts)* })
+            }
+        });
+    }
     let test_attr = if item_fn.sig.asyncness.is_some() {
         quote! {#[async_std::test]}
     } else {
         quote! {#[test]}
     };
-    let attrs = &item_fn.attrs;
-    let vis = &item_fn.vis;
     let sig = &item_fn.sig;
-    let mock_fn = if args.frozen {
-        quote! {frozen}
-    } else {
-        quote! {mock}
-    };
-    let stmts = &item_fn.block.stmts;
     Item::Fn(parse_quote! {
         #test_attr
         #(#attrs)*
         #vis #sig {
             use ::chronobreak::clock;
-            let _clock = clock::#mock_fn();
+            let _clock = clock::mock_with(#config);
             #(#stmts)*
         }
     })
 }
+
+/// Derives a test that passes the given scenario to the test function, which
+/// registers its hooks and assertions, and runs the scenario afterwards.
+fn derive_scenario_fn(
+    scenario: &LitStr,
+    config: proc_macro2::TokenStream,
+    item_fn: &ItemFn,
+) -> Item {
+    let sig = &item_fn.sig;
+    if sig.asyncness.is_some() {
+        abort! {sig, "#[chronobreak::test] scenarios may not be async"};
+    }
+    if !matches!(sig.output, ReturnType::Default) {
+        abort! {sig.output, "#[chronobreak::test] scenarios may not return a value"};
+    }
+    let param = match sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+        [FnArg::Typed(param)] => param,
+        _ => abort! {
+            sig,
+            "#[chronobreak::test] scenarios require exactly one parameter of type `&mut Scenario`"
+        },
+    };
+    let (pat, ty) = (&param.pat, &param.ty);
+    let attrs = &item_fn.attrs;
+    let vis = &item_fn.vis;
+    let ident = &sig.ident;
+    let stmts = &item_fn.block.stmts;
+    Item::Fn(parse_quote! {
+        #[test]
+        #(#attrs)*
+        #vis fn #ident() {
+            use ::chronobreak::clock;
+            let _clock = clock::mock_with(#config);
+            let mut scenario = ::chronobreak::scenario::Scenario::parse(
+                #scenario,
+                include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", #scenario)),
+            )
+            .unwrap_or_else(|err| panic!("{}", err));
+            {
+                let #pat: #ty = &mut scenario;
+                #(#stmts)*
+            }
+            scenario.run();
+        }
+    })
+}
+
+fn derive_config(args: &FnArgs) -> proc_macro2::TokenStream {
+    let frozen = args.frozen;
+    let scheduled = args.scheduled;
+    let print_stats = args.print_stats;
+    let system_time_start = match &args.start {
+        Some(start) => match timestamp::parse(&start.value()) {
+            Ok((secs, nanos)) => quote! {::std::time::Duration::new(#secs, #nanos)},
+            Err(err) => abort! {start, err},
+        },
+        None => quote! {::std::time::Duration::from_secs(0)},
+    };
+    let instant_start = match &args.instant_start {
+        Some(instant_start) => match duration::parse(&instant_start.value()) {
+            Ok(duration) => quote_duration(duration),
+            Err(err) => abort! {instant_start, err},
+        },
+        None => quote! {clock::DEFAULT_INSTANT_START},
+    };
+    let time_limit = match &args.max_time {
+        Some(max_time) => match duration::parse(&max_time.value()) {
+            Ok(duration) => {
+                let duration = quote_duration(duration);
+                quote! {Some(#duration)}
+            }
+            Err(err) => abort! {max_time, err},
+        },
+        None => quote! {None},
+    };
+    let timed_wait_limit = match args.max_timed_waits {
+        Some(max_timed_waits) => quote! {Some(#max_timed_waits)},
+        None => quote! {None},
+    };
+    quote! {
+        clock::Config {
+            frozen: #frozen,
+            scheduled: #scheduled,
+            system_time_start: #system_time_start,
+            instant_start: #instant_start,
+            time_limit: #time_limit,
+            timed_wait_limit: #timed_wait_limit,
+            print_stats_on_failure: #print_stats,
+        }
+    }
+}
+
+fn quote_duration(duration: Duration) -> proc_macro2::TokenStream {
+    let secs = duration.as_secs();
+    let nanos = duration.subsec_nanos();
+    quote! {::std::time::Duration::new(#secs, #nanos)}
+}
@@ -0,0 +1,99 @@
+/// Parses an RFC 3339 timestamp, e.g. `2024-02-29T23:59:59Z`, and returns
+/// the seconds and nanoseconds since the unix epoch.
+pub fn parse(timestamp: &str) -> Result<(u64, u32), String> {
+    let invalid = || format!("invalid RFC 3339 timestamp `{}`", timestamp);
+    let (date, time) = timestamp.split_once(['T', 't', ' ']).ok_or_else(invalid)?;
+    let (time, offset) = split_offset(time).ok_or_else(invalid)?;
+    let date = parse_fields(date, '-', 3).ok_or_else(invalid)?;
+    let (time, nanos) = match time.split_once('.') {
+        Some((time, fraction)) => (time, parse_fraction(fraction).ok_or_else(invalid)?),
+        None => (time, 0),
+    };
+    let time = parse_fields(time, ':', 3).ok_or_else(invalid)?;
+    let (year, month, day) = (date[0], date[1], date[2]);
+    let (hour, minute, second) = (time[0], time[1], time[2]);
+    if !(1..=12).contains(&month)
+        || day == 0
+        || day > days_in_month(year, month)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return Err(invalid());
+    }
+    let secs =
+        days_from_civil(year, month, day) * 86_400 + hour * 3_600 + minute * 60 + second - offset;
+    if secs < 0 {
+        return Err(format!(
+            "timestamp `{}` is before the unix epoch",
+            timestamp
+        ));
+    }
+    Ok((secs as u64, nanos))
+}
+
+/// Splits the time and the offset from UTC in seconds.
+fn split_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(time) = time.strip_suffix(['Z', 'z']) {
+        return Some((time, 0));
+    }
+    let index = time.rfind(['+', '-'])?;
+    let (time, offset) = time.split_at(index);
+    let sign = if offset.starts_with('-') { -1 } else { 1 };
+    let offset = parse_fields(&offset[1..], ':', 2)?;
+    if offset[0] > 23 || offset[1] > 59 {
+        return None;
+    }
+    Some((time, sign * (offset[0] * 3_600 + offset[1] * 60)))
+}
+
+/// Parses the given number of numeric fields separated by the given separator.
+fn parse_fields(s: &str, separator: char, count: usize) -> Option<Vec<i64>> {
+    let fields = s
+        .split(separator)
+        .map(|field| {
+            if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+                None
+            } else {
+                field.parse().ok()
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+    if fields.len() == count {
+        Some(fields)
+    } else {
+        None
+    }
+}
+
+fn parse_fraction(fraction: &str) -> Option<u32> {
+    if fraction.is_empty() || fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let digits: u32 = fraction.parse().ok()?;
+    Some(digits * 10u32.pow(9 - fraction.len() as u32))
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Returns the number of days since the unix epoch of the given date in the
+/// proleptic gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
@@ -0,0 +1,4 @@
+#[chronobreak::test(frozen, scheduled)]
+fn test() {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[chronobreak::test(max_time = "1 hour")]
+fn test() {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[chronobreak::test(start = "2023-02-29T00:00:00Z")]
+fn test() {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[chronobreak::test(scenario = "scenarios/failover.toml")]
+fn test() {}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[chronobreak::test(start = "1969-12-31T23:59:59Z")]
+fn test() {}
+
+fn main() {}
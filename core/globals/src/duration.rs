@@ -0,0 +1,42 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// Parses a human readable duration, e.g. `1h30m` or `500ms`. Supported units
+/// are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+pub fn parse(duration: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration `{}`", duration);
+    let mut rest = duration.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    let mut nanos: u128 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(invalid)?;
+        let unit_len = rest[digits..]
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len() - digits);
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let value: u128 = rest[..digits].parse().map_err(|_| invalid())?;
+        let unit = match rest[digits..digits + unit_len].trim() {
+            "d" => 86_400_000_000_000,
+            "h" => 3_600_000_000_000,
+            "m" | "min" => 60_000_000_000,
+            "s" => 1_000_000_000,
+            "ms" => 1_000_000,
+            "us" => 1_000,
+            "ns" => 1,
+            _ => return Err(invalid()),
+        };
+        nanos = value
+            .checked_mul(unit)
+            .and_then(|value| nanos.checked_add(value))
+            .ok_or_else(invalid)?;
+        rest = &rest[digits + unit_len..];
+    }
+    let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| invalid())?;
+    Ok(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
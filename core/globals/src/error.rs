@@ -0,0 +1,84 @@
+use std::fmt;
+use std::thread::ThreadId;
+use std::time::Duration;
+
+/// Errors returned by the fallible functions of the mocked clock.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ClockError {
+    /// The clock is not mocked on the current thread.
+    NotMocked,
+    /// The clock is already mocked on the current thread.
+    AlreadyMocked,
+    /// The given thread is not registered to the shared clock of the current
+    /// thread.
+    ThreadNotRegistered(ThreadId),
+    /// A mocked value has been used while the clock is not mocked or a
+    /// value created without the clock being mocked has been used while it
+    /// is mocked.
+    MixedMockedAndActual,
+    /// A thread did not enter a timed wait within the given real time.
+    ExpectTimeout(ExpectTimeout),
+}
+
+impl fmt::Display for ClockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotMocked => write!(f, "the clock is not mocked on the current thread"),
+            Self::AlreadyMocked => write!(f, "the clock is already mocked on the current thread"),
+            Self::ThreadNotRegistered(id) => write!(
+                f,
+                "{:?} is not registered to the clock of the current thread",
+                id
+            ),
+            Self::MixedMockedAndActual => {
+                write!(f, "mocked and non-mocked values may not be mixed")
+            }
+            Self::ExpectTimeout(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ClockError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::ExpectTimeout(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ExpectTimeout> for ClockError {
+    fn from(error: ExpectTimeout) -> Self {
+        Self::ExpectTimeout(error)
+    }
+}
+
+/// Error returned if a thread did not enter a timed wait within the given
+/// real time.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExpectTimeout {
+    /// The thread that was expected to enter a timed wait.
+    pub thread: ThreadId,
+    /// The name of the thread, if known.
+    pub name: Option<String>,
+    /// The real time that has been waited for.
+    pub timeout: Duration,
+    /// Description of what the thread was doing when the timeout expired.
+    pub activity: String,
+}
+
+impl fmt::Display for ExpectTimeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "thread '{}' ({:?}) did not enter a timed wait within {:?} of real time; it is {}",
+            self.name.as_deref().unwrap_or("<unnamed>"),
+            self.thread,
+            self.timeout,
+            self.activity
+        )
+    }
+}
+
+impl std::error::Error for ExpectTimeout {}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The environment variable that overrides the seed of the wait jitter.
+pub const JITTER_SEED_VAR: &str = "CHRONOBREAK_JITTER_SEED";
+
+/// The distribution of the overshoot added to timed waits.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Distribution {
+    /// Always overshoots by the given duration.
+    Constant(Duration),
+    /// Overshoots by a duration uniformly distributed between `min` and `max`,
+    /// both inclusive.
+    Uniform { min: Duration, max: Duration },
+    /// Overshoots by an exponentially distributed duration with the given
+    /// mean, i.e. mostly by little and rarely by a lot.
+    Exponential { mean: Duration },
+}
+
+/// A seeded jitter policy. Every thread draws from its own random number
+/// generator, which is seeded by the seed and the index of the thread, such
+/// that the overshoots of a thread do not depend on the order in which
+/// threads wait.
+pub(crate) struct Jitter {
+    distribution: Distribution,
+    seed: u64,
+    rngs: HashMap<usize, u64>,
+}
+
+impl Jitter {
+    pub(crate) fn new(distribution: Distribution, seed: u64) -> Self {
+        Self {
+            distribution,
+            seed,
+            rngs: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Draws the next overshoot of the thread with the given index.
+    pub(crate) fn sample(&mut self, thread_index: usize) -> Duration {
+        let seed = self.seed;
+        let rng = self
+            .rngs
+            .entry(thread_index)
+            .or_insert_with(|| seed ^ (thread_index as u64).wrapping_mul(0xa076_1d64_78bd_642f));
+        // 53 random bits, i.e. a uniformly distributed fraction in [0, 1).
+        let bits = splitmix64(rng) >> 11;
+        match self.distribution {
+            Distribution::Constant(dur) => dur,
+            Distribution::Uniform { min, max } => {
+                let range = max.saturating_sub(min).as_nanos() + 1;
+                let nanos = range.saturating_mul(u128::from(bits)) >> 53;
+                min + Duration::from_nanos(nanos.min(u128::from(u64::MAX)) as u64)
+            }
+            Distribution::Exponential { mean } => {
+                let fraction = bits as f64 / (1u64 << 53) as f64;
+                mean.mul_f64(-(1.0 - fraction).ln())
+            }
+        }
+    }
+}
+
+/// Advances the given state and returns the next random number.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
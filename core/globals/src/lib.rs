@@ -1,2 +1,9 @@
+pub mod duration;
+pub mod error;
+pub mod jitter;
 pub mod local_clock;
 pub mod shared_clock;
+pub mod spy;
+pub mod stats;
+mod time_mapping;
+pub mod trace;
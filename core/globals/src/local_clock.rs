@@ -1,10 +1,10 @@
-use crate::shared_clock::{SharedClock, Timepoint};
+use crate::shared_clock::{SharedClock, ThreadRegistration, Timepoint};
 use std::cell::RefCell;
 use std::sync::Arc;
 
 thread_local! {
     /// State of the mocked clock. None if the clock is not mocked.
-    pub static STATE: RefCell<Option<LocalClock>> = RefCell::new(None);
+    pub static STATE: RefCell<Option<LocalClock>> = const { RefCell::new(None) };
 }
 
 /// State of the local clock.
@@ -16,4 +16,7 @@ pub struct LocalClock {
     pub time: Timepoint,
     /// The shared clock.
     pub shared_clock: Arc<SharedClock>,
+    /// The registration of the current thread to the shared clock. None for
+    /// clocks that are not yet registered to a thread.
+    pub registration: Option<Arc<ThreadRegistration>>,
 }
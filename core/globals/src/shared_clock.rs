@@ -1,12 +1,28 @@
 use std::collections::{BinaryHeap, HashMap};
 use std::fmt;
 use std::ops;
+use std::panic::Location;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex, RwLock, Weak};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, RwLock, Weak};
 use std::task::Waker;
 use std::thread::{self, ThreadId};
 use std::time::Duration;
 
+pub use crate::error::{ClockError, ExpectTimeout};
+use crate::jitter::{Distribution, Jitter};
+use crate::spy::{RequestedWait, Spy, WaitKind};
+use crate::stats::{ClockStats, Stats, ThreadStats};
+pub use crate::time_mapping::Skew;
+use crate::time_mapping::{ThreadClock, TimeMapping};
+use crate::trace::{Replay, ReplayError, Trace, TraceEvent, TraceEventKind};
+
+/// The default real time after which expectations on timed waits fail.
+pub const DEFAULT_EXPECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The default time since [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START)
+/// at which mocked `Instant`s start.
+pub const DEFAULT_INSTANT_START: Duration = Duration::from_secs(86_400);
+
 /// Internal representation of the clock's current time.
 #[derive(Default, Copy, Clone, Ord, Eq, PartialEq, PartialOrd, Hash)]
 pub struct Timepoint(Duration);
@@ -21,11 +37,11 @@ impl Timepoint {
     pub const START: Self = Self(Duration::from_secs(0));
 
     pub fn checked_add(&self, duration: Duration) -> Option<Self> {
-        self.0.checked_add(duration).map(&Self)
+        self.0.checked_add(duration).map(Self)
     }
 
     pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
-        self.0.checked_sub(duration).map(&Self)
+        self.0.checked_sub(duration).map(Self)
     }
 
     pub fn duration_since(&self, earlier: Self) -> Duration {
@@ -71,88 +87,1199 @@ impl ops::SubAssign<Duration> for Timepoint {
     }
 }
 
+/// Configuration of a mocked clock.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// true if the clock is frozen on the mocking thread, otherwise false.
+    pub frozen: bool,
+    /// true if the shared clock acts as a discrete-event scheduler, otherwise
+    /// false.
+    pub scheduled: bool,
+    /// The time since the unix epoch at which the mocked system time starts.
+    pub system_time_start: Duration,
+    /// The time since [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START)
+    /// at which mocked `Instant`s start, like the uptime of a real machine.
+    /// Defaults to one day, such that e.g. `Instant::now() - Duration::from_secs(60)`
+    /// does not underflow.
+    pub instant_start: Duration,
+    /// The maximum time the shared clock may be advanced to, relative to
+    /// [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START).
+    pub time_limit: Option<Duration>,
+    /// The maximum number of sleeps and timed waits all threads registered to
+    /// the shared clock may perform in total.
+    pub timed_wait_limit: Option<u64>,
+    /// true if the [statistics](struct.SharedClock.html#method.stats) of the
+    /// shared clock are printed to stderr if the mocking thread panics while
+    /// the clock is mocked, otherwise false.
+    pub print_stats_on_failure: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            frozen: false,
+            scheduled: false,
+            system_time_start: Duration::default(),
+            instant_start: DEFAULT_INSTANT_START,
+            time_limit: None,
+            timed_wait_limit: None,
+            print_stats_on_failure: false,
+        }
+    }
+}
+
 /// State of the shared clock.
 #[derive(Default)]
 pub struct SharedClock {
-    /// The current shared time.
-    time: Mutex<Timepoint>,
+    /// The configuration of the clock.
+    config: Config,
+    /// The current shared time and the scheduling state of all registered
+    /// threads.
+    state: Mutex<State>,
     /// Condvar, which all threads who attempt to advance the shared clock
-    /// while frozen will wait on.
+    /// while frozen or scheduled will wait on.
     freeze_cond: Condvar,
+    /// Condvar, which is notified whenever a registered thread stops being
+    /// runnable or starts a timed wait.
+    waiting_cond: Condvar,
     /// Per-thread data for notifying and waiting on timed waits.
-    timed_waits: Arc<TimedWaitData>,
+    timed_waits: TimedWaitData,
     /// Queue of wakers that have to be executed as soon as the clock reaches
     /// some given time.
     wakers: Mutex<BinaryHeap<TimedWaker>>,
+    /// Mapping of the shared time to the system time.
+    system_clock: Mutex<SystemClock>,
+    /// Skew and drift of the clocks of individual threads.
+    thread_clocks: Mutex<HashMap<ThreadId, ThreadClock>>,
+    /// true if frozen threads panic once all registered threads are blocked,
+    /// otherwise false.
+    detect_deadlocks: bool,
+    /// The real time after which expectations on timed waits fail.
+    expect_timeout: Mutex<Duration>,
+    /// Limits that turn endless sleep loops into panics.
+    limits: Mutex<Limits>,
+    /// Policy for advancing the clock on reads of the current time.
+    auto_tick: Mutex<AutoTick>,
+    /// The recording of clock events, if tracing is enabled.
+    trace: Mutex<Option<Recording>>,
+    /// The trace that is being replayed, if any.
+    replay: Mutex<Option<Replay>>,
+    /// Condvar, which is notified whenever a replayed event occurred.
+    replay_cond: Condvar,
+    /// Statistics and names of all threads that performed any timing
+    /// operation.
+    stats: Mutex<HashMap<ThreadId, (Option<String>, Stats)>>,
+    /// The waits recorded by all spies.
+    spies: Mutex<Vec<Weak<Mutex<Vec<RequestedWait>>>>>,
+    /// The overshoot policy of timed waits, if any.
+    jitter: Mutex<Option<Jitter>>,
+}
+
+/// Clock events recorded since tracing has been started.
+struct Recording {
+    /// The real time at which tracing has been started.
+    start: std::time::Instant,
+    events: Vec<TraceEvent>,
+}
+
+/// Policy for advancing the clock of threads that repeatedly read the current
+/// time without the time progressing, e.g. in a busy-wait loop.
+#[derive(Default)]
+struct AutoTick {
+    /// The duration to advance by. Zero if auto-ticking is disabled.
+    tick: Duration,
+    /// The number of consecutive reads without progress after which the
+    /// clock is advanced.
+    after_reads: usize,
+    /// The local time at the last read of every thread and the number of
+    /// consecutive reads at that time.
+    reads: HashMap<ThreadId, (Timepoint, usize)>,
+}
+
+/// Limits on the time and the number of timed waits of a shared clock.
+#[derive(Default)]
+struct Limits {
+    /// The maximum time the shared clock may be advanced to.
+    time: Option<Timepoint>,
+    /// The maximum number of timed waits.
+    timed_waits: Option<u64>,
+    /// The number of timed waits performed so far.
+    timed_wait_count: u64,
+}
+
+/// Mapping of the shared time to the system time, which is neither required
+/// to be monotonic nor to advance at the same rate as the shared time.
+#[derive(Default)]
+struct SystemClock {
+    /// The mapping of the shared time to the system time.
+    mapping: TimeMapping,
+    /// The total duration the system has been suspended for.
+    suspended: Duration,
+}
+
+/// State of the shared clock that must be modified atomically.
+#[derive(Default)]
+struct State {
+    /// The current shared time.
+    time: Timepoint,
+    /// All registered threads that did not exit yet and their names.
+    threads: HashMap<ThreadId, Option<String>>,
+    /// Threads that are blocked in a frozen or scheduled timed wait.
+    timed_waiting: HashMap<ThreadId, TimedWait>,
+    /// The most recent frozen or scheduled timed wait of every thread.
+    last_timed_wait: HashMap<ThreadId, TimedWait>,
+    /// Threads that are blocked outside of a timed wait and what they wait
+    /// for.
+    blocked: HashMap<ThreadId, Blocker>,
+    /// Number of threads per blocker that have been woken up but did not yet
+    /// resume.
+    wakeups: HashMap<Blocker, usize>,
+    /// Report of a detected deadlock, which all frozen threads panic with.
+    /// Reset as soon as a waiter is woken up or the shared time advances.
+    deadlock: Option<String>,
+    /// The index of every thread in the order in which the threads
+    /// registered.
+    thread_indices: HashMap<ThreadId, usize>,
+}
+
+/// A timed wait of a single thread.
+#[derive(Copy, Clone)]
+struct TimedWait {
+    /// The local time of the thread when it started waiting.
+    local_time: Timepoint,
+    /// The time the thread waits for.
+    deadline: Timepoint,
+}
+
+impl State {
+    /// Returns the index of the given thread, assigning the next free index
+    /// to unknown threads.
+    fn thread_index(&mut self, id: ThreadId) -> usize {
+        let next = self.thread_indices.len();
+        *self.thread_indices.entry(id).or_insert(next)
+    }
+
+    fn blocked_on(&self, blocker: Blocker) -> usize {
+        self.blocked.values().filter(|b| **b == blocker).count()
+    }
+
+    fn runnable(&self) -> usize {
+        let wakeups: usize = self.wakeups.values().sum();
+        (self.threads.len() + wakeups).saturating_sub(self.timed_waiting.len() + self.blocked.len())
+    }
+
+    /// Describes what the given thread is currently doing.
+    fn activity(&self, id: ThreadId) -> String {
+        let activity = if !self.threads.contains_key(&id) {
+            return "not registered to the clock or exited".to_string();
+        } else if let Some(wait) = self.timed_waiting.get(&id) {
+            return format!(
+                "in a timed wait from local time {:?} until {:?}",
+                wait.local_time, wait.deadline
+            );
+        } else if let Some(blocker) = self.blocked.get(&id) {
+            format!("blocked on {:?}", blocker)
+        } else {
+            "runnable".to_string()
+        };
+        match self.last_timed_wait.get(&id) {
+            Some(wait) => format!(
+                "{}, its last timed wait was from local time {:?} until {:?}",
+                activity, wait.local_time, wait.deadline
+            ),
+            None => format!("{}, it did not perform any timed wait yet", activity),
+        }
+    }
+
+    /// Returns a report of the state of all registered threads.
+    fn report(&self) -> String {
+        let mut threads: Vec<_> = self.threads.iter().collect();
+        threads.sort_by_key(|(id, _)| format!("{:?}", id));
+        let mut report = format!("shared time: {:?}", self.time);
+        for (id, name) in threads {
+            let name = name.as_deref().unwrap_or("<unnamed>");
+            let status = if let Some(wait) = self.timed_waiting.get(id) {
+                format!(
+                    "timed wait at local time {:?} until {:?}",
+                    wait.local_time, wait.deadline
+                )
+            } else if let Some(blocker) = self.blocked.get(id) {
+                format!("blocked on {:?}", blocker)
+            } else {
+                "runnable".to_string()
+            };
+            report += &format!("\n  thread '{}' ({:?}): {}", name, id, status);
+        }
+        report
+    }
+
+    fn unblock(&mut self, blocker: Blocker, count: usize) {
+        let wakeups = self.wakeups.get(&blocker).copied().unwrap_or_default();
+        let wakeups = self.blocked_on(blocker).min(wakeups + count);
+        if wakeups > 0 {
+            self.wakeups.insert(blocker, wakeups);
+            self.deadlock = None;
+        }
+    }
+}
+
+/// A timer registered to the shared clock, e.g. by a pending `DelayFuture`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct PendingTimer {
+    /// The time at which the timer fires.
+    pub deadline: Timepoint,
+    /// The thread that registered the timer.
+    pub owner: ThreadId,
+}
+
+/// A thread that is blocked in a frozen or scheduled timed wait.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TimedWaiter {
+    /// The waiting thread.
+    pub thread: ThreadId,
+    /// The time the thread waits for.
+    pub deadline: Timepoint,
+}
+
+/// Identifies what a thread that is blocked outside of a timed wait waits for.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Blocker {
+    /// The thread waits for the given thread to exit.
+    Thread(ThreadId),
+    /// The thread waits on the synchronization primitive at the given address.
+    Address(usize),
+    /// The thread is parked until the given thread, i.e. itself, is
+    /// unparked, e.g. by a mocked `thread::park_timeout`.
+    Park(ThreadId),
 }
 
 impl SharedClock {
-    pub fn register_thread(&self) {
-        self.timed_waits
-            .write()
+    pub fn new(config: Config) -> Self {
+        let system_clock = SystemClock {
+            mapping: TimeMapping::starting_at(Timepoint::START + config.system_time_start),
+            ..Default::default()
+        };
+        let limits = Limits {
+            time: config.time_limit.map(|limit| Timepoint::START + limit),
+            timed_waits: config.timed_wait_limit,
+            ..Default::default()
+        };
+        Self {
+            config,
+            system_clock: Mutex::new(system_clock),
+            detect_deadlocks: true,
+            expect_timeout: Mutex::new(DEFAULT_EXPECT_TIMEOUT),
+            limits: Mutex::new(limits),
+            ..Default::default()
+        }
+    }
+
+    /// Disables the deadlock detection, e.g. for shared clocks that may be
+    /// advanced by threads that are not registered to them.
+    pub fn without_deadlock_detection(self) -> Self {
+        Self {
+            detect_deadlocks: false,
+            ..self
+        }
+    }
+
+    pub fn is_scheduled(&self) -> bool {
+        self.config.scheduled
+    }
+
+    /// Returns the time since [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START)
+    /// at which mocked `Instant`s start.
+    pub fn instant_start(&self) -> Duration {
+        self.config.instant_start
+    }
+
+    /// Returns the system time that corresponds to the given time.
+    pub fn system_time(&self, time: Timepoint) -> Timepoint {
+        self.system_clock.lock().unwrap().mapping.map(time)
+    }
+
+    /// Sets the system time at the given time. Later system times advance
+    /// from this system time.
+    pub fn set_system_time(&self, time: Timepoint, system_time: Timepoint) {
+        let mut system_clock = self.system_clock.lock().unwrap();
+        system_clock.mapping.set(time, system_time);
+    }
+
+    /// Returns the boot time that corresponds to the given time. In contrast
+    /// to the shared time, the boot time advances while the system is
+    /// suspended.
+    pub fn boot_time(&self, time: Timepoint) -> Timepoint {
+        time + self.system_clock.lock().unwrap().suspended
+    }
+
+    /// Simulates a system suspend for the given duration at the given time.
+    /// The system and boot time advance by the given duration, while the
+    /// shared time does not.
+    pub fn suspend_for(&self, time: Timepoint, dur: Duration) {
+        let mut system_clock = self.system_clock.lock().unwrap();
+        let system_time = system_clock.mapping.map(time);
+        system_clock.mapping.set(time, system_time + dur);
+        system_clock.suspended += dur;
+    }
+
+    /// Changes the rate of the system time from the given time on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ppm` is not greater than -1,000,000, which
+    /// would stop the system time or make it run backwards.
+    pub fn slew_system_time(&self, time: Timepoint, ppm: i64) {
+        let mut system_clock = self.system_clock.lock().unwrap();
+        system_clock.mapping.set_rate(time, ppm);
+    }
+
+    /// Returns the time the current thread observes at the given time, i.e.
+    /// the given time converted by `derive`, e.g. to the system time, with the
+    /// thread's skew and drift applied.
+    pub fn observed_time<F>(&self, time: Timepoint, derive: F) -> Timepoint
+    where
+        F: FnOnce(Timepoint) -> Timepoint,
+    {
+        let thread_clock = self
+            .thread_clocks
+            .lock()
             .unwrap()
-            .insert(thread::current().id(), Default::default());
+            .get(&thread::current().id())
+            .copied()
+            .unwrap_or_default();
+        thread_clock.map(time, derive)
+    }
+
+    /// Sets the constant offset of the given thread's clock.
+    pub fn set_skew(&self, id: ThreadId, skew: Skew) {
+        self.thread_clocks
+            .lock()
+            .unwrap()
+            .entry(id)
+            .or_default()
+            .skew = skew;
+    }
+
+    /// Changes the rate of the given thread's clock from the current shared
+    /// time on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ppm` is not greater than -1,000,000.
+    pub fn set_drift(&self, id: ThreadId, ppm: i64) {
+        let time = self.time();
+        self.thread_clocks
+            .lock()
+            .unwrap()
+            .entry(id)
+            .or_default()
+            .drift
+            .set_rate(time, ppm);
+    }
+
+    /// Returns the current shared time.
+    pub fn time(&self) -> Timepoint {
+        self.state.lock().unwrap().time
+    }
+
+    pub fn register_thread(self: &Arc<Self>) -> ThreadRegistration {
+        let id = thread::current().id();
+        self.timed_waits.write().unwrap().entry(id).or_default();
+        let mut state = self.state.lock().unwrap();
+        state
+            .threads
+            .insert(id, thread::current().name().map(str::to_string));
+        state.thread_index(id);
+        drop(state);
+        ThreadRegistration {
+            shared_clock: self.clone(),
+            id,
+        }
+    }
+
+    fn unregister_thread(&self, id: ThreadId) {
+        let mut state = self.state.lock().unwrap();
+        state.threads.remove(&id);
+        self.waiting_cond.notify_all();
+        let blocked = state.blocked_on(Blocker::Thread(id));
+        state.unblock(Blocker::Thread(id), blocked);
+        self.detect_deadlock(&mut state);
+        drop(self.run_scheduler(state));
     }
 
-    pub fn advance_to(&self, time: Timepoint) {
-        let mut global_time = self.time.lock().unwrap();
-        if *global_time < time {
-            let _guard = self.notify_timed_wait();
-            while *global_time < time {
-                global_time = self.freeze_cond.wait(global_time).unwrap();
+    /// Blocks the current thread until some other thread advances the shared
+    /// clock to the given time. Returns an error if the current thread is not
+    /// registered to the shared clock.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if all registered threads are blocked, as the
+    /// shared clock can then no longer advance.
+    pub fn advance_to(&self, local_time: Timepoint, time: Timepoint) -> Result<(), ClockError> {
+        let mut state = self.state.lock().unwrap();
+        if state.time < time {
+            let _guard = self.notify_timed_wait(local_time, time)?;
+            let id = thread::current().id();
+            let wait = TimedWait {
+                local_time,
+                deadline: time,
+            };
+            state.timed_waiting.insert(id, wait);
+            state.last_timed_wait.insert(id, wait);
+            self.waiting_cond.notify_all();
+            self.detect_deadlock(&mut state);
+            while state.time < time {
+                if let Some(report) = state.deadlock.clone() {
+                    state.timed_waiting.remove(&id);
+                    drop(state);
+                    panic! {"chronobreak detected a deadlock: all threads registered to the frozen clock are blocked\n{}", report};
+                }
+                state = self.freeze_cond.wait(state).unwrap();
             }
         }
+        Ok(())
     }
 
-    pub fn unfreeze_advance_to(&self, time: Timepoint) {
-        let mut global_time = self.time.lock().unwrap();
-        if *global_time < time {
-            *global_time = time;
+    /// Detects whether all registered threads are blocked while at least one
+    /// of them waits for a frozen clock, which nobody can advance anymore.
+    /// Frozen waiters are then woken up, such that they can panic. A deadlock
+    /// that has been detected before is reset if some thread is runnable
+    /// again, e.g. as a waiter caught its panic.
+    fn detect_deadlock(&self, state: &mut State) {
+        if !self.detect_deadlocks || self.config.scheduled {
+            return;
+        }
+        if state.timed_waiting.is_empty() || state.runnable() > 0 {
+            state.deadlock = None;
+        } else if state.deadlock.is_none() {
+            state.deadlock = Some(state.report());
             self.freeze_cond.notify_all();
-            let mut wakers = self.wakers.lock().unwrap();
-            while let Some(timed_waker) = wakers.peek() {
-                if timed_waker.timeout <= time {
-                    if let Some(waker) = wakers.pop().unwrap().waker.upgrade() {
-                        waker.wake_by_ref();
-                    }
-                } else {
-                    break;
+        }
+    }
+
+    pub fn unfreeze_advance_to(&self, time: Timepoint) {
+        let mut state = self.state.lock().unwrap();
+        if state.time < time {
+            let wakers = self.set_time(&mut state, time);
+            drop(state);
+            wake(wakers);
+        }
+    }
+
+    /// Advances the shared clock by the given duration without blocking,
+    /// like [`unfreeze_advance_to`](#method.unfreeze_advance_to) called by a
+    /// thread whose local time equals the shared time.
+    pub fn advance_by(&self, dur: Duration) {
+        let mut state = self.state.lock().unwrap();
+        let time = state.time + dur;
+        let wakers = self.set_time(&mut state, time);
+        drop(state);
+        wake(wakers);
+    }
+
+    /// Blocks the current thread until the shared clock reaches the given
+    /// time. The shared clock is advanced to the earliest time any thread
+    /// waits for as soon as all registered threads are blocked. Returns an
+    /// error if the current thread is not registered to the shared clock.
+    pub fn scheduled_advance_to(
+        &self,
+        local_time: Timepoint,
+        time: Timepoint,
+    ) -> Result<(), ClockError> {
+        let mut state = self.state.lock().unwrap();
+        if state.time < time {
+            let _guard = self.notify_timed_wait(local_time, time)?;
+            let wait = TimedWait {
+                local_time,
+                deadline: time,
+            };
+            state.timed_waiting.insert(thread::current().id(), wait);
+            state.last_timed_wait.insert(thread::current().id(), wait);
+            self.waiting_cond.notify_all();
+            state = self.run_scheduler(state);
+            while state.time < time {
+                state = self.freeze_cond.wait(state).unwrap();
+            }
+        }
+        Ok(())
+    }
+
+    /// Advances the shared clock to the earliest time any thread or waker
+    /// waits for, if the clock is scheduled and no registered thread is
+    /// runnable. Returns the wakers that have to be woken up.
+    #[must_use]
+    fn schedule(&self, state: &mut State) -> Vec<Arc<Waker>> {
+        if !self.config.scheduled || state.runnable() > 0 {
+            return Vec::new();
+        }
+        let next_waker = self.next_waker_timeout();
+        let next_thread = state.timed_waiting.values().map(|wait| wait.deadline).min();
+        match next_waker.into_iter().chain(next_thread).min() {
+            Some(time) => self.set_time(state, time),
+            None => Vec::new(),
+        }
+    }
+
+    /// Repeatedly [schedules](#method.schedule) and wakes up the returned
+    /// wakers, until a registered thread is runnable or nothing is pending.
+    /// This is required as woken wakers, e.g. of scheduled callbacks, do not
+    /// necessarily make any thread runnable. The state is unlocked while
+    /// waking and returned locked again.
+    fn run_scheduler<'a>(&'a self, mut state: MutexGuard<'a, State>) -> MutexGuard<'a, State> {
+        loop {
+            let wakers = self.schedule(&mut state);
+            if wakers.is_empty() {
+                return state;
+            }
+            drop(state);
+            wake(wakers);
+            state = self.state.lock().unwrap();
+        }
+    }
+
+    /// Returns all timers whose wakers have not been dropped yet, ordered by
+    /// their deadline.
+    pub fn pending_timers(&self) -> Vec<PendingTimer> {
+        let mut timers: Vec<_> = self
+            .wakers
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|timed_waker| timed_waker.waker.strong_count() > 0)
+            .map(|timed_waker| PendingTimer {
+                deadline: timed_waker.timeout,
+                owner: timed_waker.owner,
+            })
+            .collect();
+        timers.sort_by_key(|timer| timer.deadline);
+        timers
+    }
+
+    /// Returns all threads that are currently blocked in a frozen or scheduled
+    /// timed wait, ordered by the time they wait for.
+    pub fn timed_waiters(&self) -> Vec<TimedWaiter> {
+        let mut waiters: Vec<_> = self
+            .state
+            .lock()
+            .unwrap()
+            .timed_waiting
+            .iter()
+            .map(|(thread, wait)| TimedWaiter {
+                thread: *thread,
+                deadline: wait.deadline,
+            })
+            .collect();
+        waiters.sort_by_key(|waiter| waiter.deadline);
+        waiters
+    }
+
+    /// Blocks the current thread until all other registered threads are
+    /// blocked in a timed wait or a mocked blocking routine, or have exited.
+    pub fn wait_until_idle(&self) {
+        let id = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        while state.runnable() > usize::from(state.threads.contains_key(&id)) {
+            state = self.waiting_cond.wait(state).unwrap();
+        }
+    }
+
+    /// Returns the earliest time any pending timer or timed waiting thread
+    /// waits for.
+    pub fn next_deadline(&self) -> Option<Timepoint> {
+        let state = self.state.lock().unwrap();
+        let next_thread = state.timed_waiting.values().map(|wait| wait.deadline).min();
+        self.next_waker_timeout()
+            .into_iter()
+            .chain(next_thread)
+            .min()
+    }
+
+    /// Returns the earliest timeout of all wakers that have not been dropped.
+    fn next_waker_timeout(&self) -> Option<Timepoint> {
+        let mut wakers = self.wakers.lock().unwrap();
+        while let Some(timed_waker) = wakers.peek() {
+            if timed_waker.waker.strong_count() > 0 {
+                return Some(timed_waker.timeout);
+            }
+            wakers.pop();
+        }
+        None
+    }
+
+    /// Sets the shared time and returns the wakers that have to be woken up.
+    /// The wakers must be woken up after the state has been unlocked, as they
+    /// may access the shared clock.
+    #[must_use]
+    fn set_time(&self, state: &mut State, time: Timepoint) -> Vec<Arc<Waker>> {
+        state.time = time;
+        state.deadlock = None;
+        state.timed_waiting.retain(|_, wait| wait.deadline > time);
+        self.freeze_cond.notify_all();
+        let mut wakers = self.wakers.lock().unwrap();
+        let mut result = Vec::new();
+        while let Some(timed_waker) = wakers.peek() {
+            if timed_waker.timeout <= time {
+                let TimedWaker {
+                    waker, scheduled, ..
+                } = wakers.pop().unwrap();
+                if let Some(waker) = scheduled.or_else(|| waker.upgrade()) {
+                    result.push(waker);
                 }
+            } else {
+                break;
             }
         }
+        result
     }
 
-    pub fn notify_timed_wait(&self) -> TimedWaitGuard {
-        let lock = self.timed_waits.read().unwrap();
-        let thread_info = lock
-            .get(&thread::current().id())
-            .expect("chronobreak internal error: thread was not registered");
-        let _lock = thread_info.1.lock().unwrap();
-        thread_info.0.fetch_add(1, Ordering::SeqCst);
-        thread_info.2.notify_all();
-        TimedWaitGuard::new(self.timed_waits.clone())
+    /// Marks the current thread as blocked on the given blocker until the
+    /// returned guard is dropped.
+    pub fn notify_blocking(self: &Arc<Self>, blocker: Blocker) -> BlockingGuard {
+        self.notify_blocking_unless(blocker, || false)
     }
 
-    pub fn expect_timed_wait_on(&self, id: ThreadId) {
-        let lock = self.timed_waits.read().unwrap();
-        let timed_waits = lock
+    /// Similar to [`notify_blocking`](#method.notify_blocking) but the thread
+    /// is not marked as blocked if `woken` returns true. `woken` is evaluated
+    /// atomically with respect to [`unblock_one`](#method.unblock_one) and
+    /// [`unblock_all`](#method.unblock_all).
+    pub fn notify_blocking_unless<F>(self: &Arc<Self>, blocker: Blocker, woken: F) -> BlockingGuard
+    where
+        F: FnOnce() -> bool,
+    {
+        let id = thread::current().id();
+        let mut state = self.state.lock().unwrap();
+        let exited =
+            matches!(blocker, Blocker::Thread(thread) if !state.threads.contains_key(&thread));
+        if !exited && state.threads.contains_key(&id) && !woken() {
+            state.blocked.insert(id, blocker);
+            self.waiting_cond.notify_all();
+            self.detect_deadlock(&mut state);
+            drop(self.run_scheduler(state));
+        }
+        BlockingGuard {
+            shared_clock: self.clone(),
+            id,
+        }
+    }
+
+    /// Wakes up at most one thread that is blocked on the given blocker.
+    pub fn unblock_one(&self, blocker: Blocker) {
+        self.state.lock().unwrap().unblock(blocker, 1);
+    }
+
+    /// Wakes up all threads that are blocked on the given blocker.
+    pub fn unblock_all(&self, blocker: Blocker) {
+        let mut state = self.state.lock().unwrap();
+        let blocked = state.blocked_on(blocker);
+        state.unblock(blocker, blocked);
+    }
+
+    /// Notifies that the current thread starts a timed wait at the given
+    /// local time until the given deadline. The wait ends when the returned
+    /// guard is dropped. The state must be locked by the caller.
+    fn notify_timed_wait(
+        &self,
+        started_at: Timepoint,
+        deadline: Timepoint,
+    ) -> Result<TimedWaitGuard, ClockError> {
+        let thread_info = self.thread_timed_waits(thread::current().id())?;
+        let mut history = thread_info.history.lock().unwrap();
+        thread_info.active.fetch_add(1, Ordering::SeqCst);
+        history.generation += 1;
+        history.last = Some(TimedWaitInfo {
+            deadline,
+            started_at,
+        });
+        thread_info.started.notify_all();
+        drop(history);
+        self.waiting_cond.notify_all();
+        Ok(TimedWaitGuard { thread_info })
+    }
+
+    /// Returns the data for notifying and waiting on timed waits of the given
+    /// thread. The data is cloned, such that waiting on it does not block the
+    /// registration of other threads.
+    fn thread_timed_waits(&self, id: ThreadId) -> Result<Arc<ThreadTimedWaits>, ClockError> {
+        let timed_waits = self.timed_waits.read().unwrap();
+        timed_waits
             .get(&id)
-            .expect("chronobreak internal error: thread was not registered");
-        let mut lock = timed_waits.1.lock().unwrap();
-        while timed_waits.0.load(Ordering::SeqCst) == 0 {
-            lock = timed_waits.2.wait(lock).unwrap();
+            .cloned()
+            .ok_or(ClockError::ThreadNotRegistered(id))
+    }
+
+    /// Blocks the current thread until the given thread enters a timed wait
+    /// or the given real time has passed.
+    pub fn expect_timed_wait_on(&self, id: ThreadId, timeout: Duration) -> Result<(), ClockError> {
+        self.wait_for_timed_waits(id, timeout, |thread_info, _| {
+            thread_info.active.load(Ordering::SeqCst) > 0
+        })
+        .map(|_| ())
+    }
+
+    /// Returns the number of timed waits the given thread has started so far.
+    pub fn timed_wait_generation(&self, id: ThreadId) -> Result<u64, ClockError> {
+        let thread_info = self.thread_timed_waits(id)?;
+        let generation = thread_info.history.lock().unwrap().generation;
+        Ok(generation)
+    }
+
+    /// Blocks the current thread until the given thread has started at least
+    /// `count` timed waits in total or the given real time has passed. Returns
+    /// the most recent timed wait of the thread.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `count` is zero.
+    pub fn expect_timed_waits_on(
+        &self,
+        id: ThreadId,
+        count: u64,
+        timeout: Duration,
+    ) -> Result<TimedWaitInfo, ClockError> {
+        assert! {count > 0, "at least one timed wait must be expected"};
+        self.wait_for_timed_waits(id, timeout, |_, history| history.generation >= count)
+            .map(|history| history.last.unwrap())
+    }
+
+    /// Blocks the current thread until `count` other registered threads, or
+    /// all of them if `count` is `None`, are in a timed wait or the given real
+    /// time has passed. Only threads whose name starts with `prefix` are
+    /// considered, if given.
+    pub fn expect_timed_waits(
+        &self,
+        prefix: Option<&str>,
+        count: Option<usize>,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let id = thread::current().id();
+        let counts = |state: &State| {
+            let timed_waits = self.timed_waits.read().unwrap();
+            let threads: Vec<_> = state
+                .threads
+                .iter()
+                .filter(|(thread, name)| {
+                    **thread != id
+                        && prefix.is_none_or(|prefix| {
+                            name.as_deref().is_some_and(|name| name.starts_with(prefix))
+                        })
+                })
+                .collect();
+            let waiting = threads
+                .iter()
+                .filter(|(thread, _)| {
+                    timed_waits
+                        .get(thread)
+                        .is_some_and(|thread_info| thread_info.active.load(Ordering::SeqCst) > 0)
+                })
+                .count();
+            (waiting, count.unwrap_or(threads.len()))
+        };
+        let state = self.state.lock().unwrap();
+        let (state, result) = self
+            .waiting_cond
+            .wait_timeout_while(state, timeout, |state| {
+                let (waiting, expected) = counts(state);
+                waiting < expected
+            })
+            .unwrap();
+        if result.timed_out() {
+            let (waiting, expected) = counts(&state);
+            Err(format!(
+                "only {} of {} expected threads entered a timed wait within {:?} of real time\n{}",
+                waiting,
+                expected,
+                timeout,
+                state.report()
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn wait_for_timed_waits<F>(
+        &self,
+        id: ThreadId,
+        timeout: Duration,
+        done: F,
+    ) -> Result<TimedWaitHistory, ClockError>
+    where
+        F: Fn(&ThreadTimedWaits, &TimedWaitHistory) -> bool,
+    {
+        let thread_info = self.thread_timed_waits(id)?;
+        let history = thread_info.history.lock().unwrap();
+        let (history, result) = thread_info
+            .started
+            .wait_timeout_while(history, timeout, |history| !done(&thread_info, history))
+            .unwrap();
+        // The state is locked before the history when a timed wait starts, so
+        // the history must be unlocked before locking the state.
+        let history = *history;
+        if result.timed_out() {
+            let state = self.state.lock().unwrap();
+            Err(ClockError::ExpectTimeout(ExpectTimeout {
+                thread: id,
+                name: state.threads.get(&id).cloned().flatten(),
+                timeout,
+                activity: state.activity(id),
+            }))
+        } else {
+            Ok(history)
+        }
+    }
+
+    /// Returns the real time after which expectations on timed waits fail.
+    pub fn expect_timeout(&self) -> Duration {
+        *self.expect_timeout.lock().unwrap()
+    }
+
+    /// Sets the real time after which expectations on timed waits fail.
+    pub fn set_expect_timeout(&self, timeout: Duration) {
+        *self.expect_timeout.lock().unwrap() = timeout;
+    }
+
+    /// Sets the maximum time the shared clock may be advanced to, relative
+    /// to [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START).
+    pub fn set_time_limit(&self, limit: Option<Duration>) {
+        self.limits.lock().unwrap().time = limit.map(|limit| Timepoint::START + limit);
+    }
+
+    /// Sets the maximum number of timed waits all registered threads may
+    /// perform in total.
+    pub fn set_timed_wait_limit(&self, limit: Option<u64>) {
+        self.limits.lock().unwrap().timed_waits = limit;
+    }
+
+    /// Checks an advance of the current thread to the given time against the
+    /// limits of the shared clock, counting it as a sleep or timed wait if
+    /// `timed_wait` is true. Returns a description of the violated limit, if
+    /// any.
+    pub fn check_limits(&self, time: Timepoint, timed_wait: bool) -> Result<(), String> {
+        let mut limits = self.limits.lock().unwrap();
+        if timed_wait {
+            limits.timed_wait_count += 1;
+        }
+        let violation = match (limits.time, limits.timed_waits) {
+            (Some(limit), _) if time > limit => format!(
+                "attempted to advance the clock to {:?}, beyond the time limit of {:?}",
+                time, limit
+            ),
+            (_, Some(limit)) if limits.timed_wait_count > limit => format!(
+                "attempted to perform more than {} timed waits in total",
+                limit
+            ),
+            _ => return Ok(()),
+        };
+        drop(limits);
+        let thread = thread::current();
+        Err(format!(
+            "thread '{}' ({:?}) {}\n{}",
+            thread.name().unwrap_or("<unnamed>"),
+            thread.id(),
+            violation,
+            self.state.lock().unwrap().report()
+        ))
+    }
+
+    /// Advances the clock of threads by `tick` after they read the current
+    /// time `after_reads` times in a row without the time progressing. A zero
+    /// `tick` disables auto-ticking.
+    pub fn set_auto_tick(&self, after_reads: usize, tick: Duration) {
+        let mut auto_tick = self.auto_tick.lock().unwrap();
+        auto_tick.tick = tick;
+        auto_tick.after_reads = after_reads.max(1);
+        auto_tick.reads.clear();
+    }
+
+    /// Counts a read of the current time by the current thread at the given
+    /// local time. Returns the duration the thread's clock has to be advanced
+    /// by, if any.
+    pub fn auto_tick(&self, local_time: Timepoint) -> Option<Duration> {
+        let mut auto_tick = self.auto_tick.lock().unwrap();
+        if auto_tick.tick == Duration::default() {
+            return None;
+        }
+        let (tick, after_reads) = (auto_tick.tick, auto_tick.after_reads);
+        let reads = auto_tick
+            .reads
+            .entry(thread::current().id())
+            .or_insert((local_time, 0));
+        if reads.0 != local_time {
+            *reads = (local_time, 0);
+        }
+        reads.1 += 1;
+        if reads.1 >= after_reads {
+            *reads = (local_time + tick, 0);
+            Some(tick)
+        } else {
+            None
+        }
+    }
+
+    /// Sets the distribution and the seed of the overshoot added to timed
+    /// waits. `None` disables the jitter.
+    pub fn set_wait_jitter(&self, jitter: Option<(Distribution, u64)>) {
+        *self.jitter.lock().unwrap() =
+            jitter.map(|(distribution, seed)| Jitter::new(distribution, seed));
+    }
+
+    /// Returns the seed of the wait jitter, if enabled.
+    pub fn wait_jitter_seed(&self) -> Option<u64> {
+        self.jitter.lock().unwrap().as_ref().map(Jitter::seed)
+    }
+
+    /// Draws the overshoot of the next timed wait of the current thread.
+    /// Returns zero if the jitter is disabled.
+    pub fn wait_jitter(&self) -> Duration {
+        let mut jitter = self.jitter.lock().unwrap();
+        match jitter.as_mut() {
+            Some(jitter) => {
+                let thread_index = self
+                    .state
+                    .lock()
+                    .unwrap()
+                    .thread_index(thread::current().id());
+                jitter.sample(thread_index)
+            }
+            None => Duration::default(),
+        }
+    }
+
+    /// Returns a spy that records all waits requested from now on until it
+    /// is dropped.
+    pub fn spy(&self) -> Spy {
+        let spy = Spy::new();
+        self.spies.lock().unwrap().push(spy.downgrade());
+        spy
+    }
+
+    /// Records a wait for the given duration, which the current thread
+    /// requested at the given location, with all alive spies.
+    pub fn spy_wait(
+        &self,
+        kind: WaitKind,
+        duration: Duration,
+        location: &'static Location<'static>,
+    ) {
+        let mut spies = self.spies.lock().unwrap();
+        spies.retain(|spy| spy.strong_count() > 0);
+        if spies.is_empty() {
+            return;
+        }
+        let thread = thread::current();
+        let wait = RequestedWait {
+            kind,
+            duration,
+            location,
+            thread: thread.id(),
+            thread_name: thread.name().map(str::to_string),
+        };
+        for spy in spies.iter().filter_map(Weak::upgrade) {
+            spy.lock().unwrap().push(wait.clone());
+        }
+    }
+
+    /// Updates the statistics of the current thread with the given function.
+    pub fn count<F>(&self, f: F)
+    where
+        F: FnOnce(&mut Stats),
+    {
+        let mut stats = self.stats.lock().unwrap();
+        let thread = thread::current();
+        let (_, stats) = stats
+            .entry(thread.id())
+            .or_insert_with(|| (thread.name().map(str::to_string), Stats::default()));
+        f(stats)
+    }
+
+    /// Returns the statistics of all threads.
+    pub fn stats(&self) -> ClockStats {
+        let stats = self.stats.lock().unwrap();
+        let mut state = self.state.lock().unwrap();
+        let threads = stats
+            .iter()
+            .map(|(id, (name, stats))| ThreadStats {
+                thread: *id,
+                thread_index: state.thread_index(*id),
+                thread_name: name.clone(),
+                stats: *stats,
+            })
+            .collect();
+        ClockStats::new(threads)
+    }
+
+    /// Returns true if the statistics are printed if the mocking thread
+    /// panics.
+    pub fn print_stats_on_failure(&self) -> bool {
+        self.config.print_stats_on_failure
+    }
+
+    /// Starts recording clock events, discarding all previously recorded
+    /// events.
+    pub fn start_trace(&self) {
+        *self.trace.lock().unwrap() = Some(Recording {
+            start: std::time::Instant::now(),
+            events: Vec::new(),
+        });
+    }
+
+    /// Stops recording clock events and returns the recorded events.
+    pub fn stop_trace(&self) -> Trace {
+        let recording = self.trace.lock().unwrap().take();
+        Trace::new(
+            recording
+                .map(|recording| recording.events)
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Returns the events recorded so far without stopping the recording.
+    pub fn trace(&self) -> Trace {
+        let recording = self.trace.lock().unwrap();
+        Trace::new(
+            recording
+                .as_ref()
+                .map(|recording| recording.events.clone())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Records an event of the current thread at the given local time, which
+    /// started at the real time `started`, if tracing is enabled. While a
+    /// trace is [replayed](#method.start_replay), the event is checked
+    /// against the trace, for which the thread first waits until the event is
+    /// due unless it already [awaited its turn](#method.await_replay_turn).
+    /// Returns an error if the event diverges from the trace.
+    pub fn record(
+        self: &Arc<Self>,
+        kind: TraceEventKind,
+        local_time: Timepoint,
+        started: std::time::Instant,
+    ) -> Result<(), ReplayError> {
+        let thread = thread::current();
+        let mut event = TraceEvent {
+            kind,
+            thread: Some(thread.id()),
+            thread_index: self.state.lock().unwrap().thread_index(thread.id()),
+            thread_name: thread.name().map(str::to_string),
+            local_time,
+            real_time: Duration::default(),
+            real_duration: Duration::default(),
+        };
+        let result = self.await_replay(&event, |replay| replay.turn(&event));
+        let mut trace = self.trace.lock().unwrap();
+        if let Some(recording) = trace.as_mut() {
+            event.real_time = started.saturating_duration_since(recording.start);
+            event.real_duration = started.elapsed();
+            recording.events.push(event);
+        }
+        result
+    }
+
+    /// Blocks the current thread until its next event is due according to
+    /// the replayed trace, if any. Threads call this before the side effect
+    /// of an event, e.g. advancing the shared clock, and
+    /// [record](#method.record) the event afterwards, which forces the side
+    /// effects into the recorded order. While waiting, the thread is
+    /// considered blocked by the scheduler.
+    pub fn await_replay_turn(self: &Arc<Self>) -> Result<(), ReplayError> {
+        if self.replay.lock().unwrap().is_none() {
+            return Ok(());
+        }
+        let thread_index = self
+            .state
+            .lock()
+            .unwrap()
+            .thread_index(thread::current().id());
+        let waiter = format!("thread #{}", thread_index);
+        self.await_replay(&waiter, |replay| Ok(replay.is_turn_of(thread_index)))
+    }
+
+    /// Starts replaying the given trace. Until the replay is
+    /// [finished](#method.finish_replay), every recorded event waits until it
+    /// is due according to the trace, which forces the threads into the
+    /// recorded order. Threads are identified by the order in which they
+    /// registered to the shared clock.
+    pub fn start_replay(&self, trace: &Trace) {
+        *self.replay.lock().unwrap() = Some(Replay::new(trace));
+        self.replay_cond.notify_all();
+    }
+
+    /// Stops replaying and returns the first divergence from the replayed
+    /// trace, if any.
+    pub fn finish_replay(&self) -> Result<(), ReplayError> {
+        let replay = self.replay.lock().unwrap().take();
+        self.replay_cond.notify_all();
+        replay.map_or(Ok(()), Replay::finish)
+    }
+
+    /// Blocks the current thread until `turn` returns true for the replayed
+    /// trace, if any, or an error. While waiting, the thread is considered
+    /// blocked by the scheduler. Returns an error if the given waiter does
+    /// not get its turn within the expect timeout.
+    fn await_replay<F>(
+        self: &Arc<Self>,
+        waiter: &dyn fmt::Display,
+        mut turn: F,
+    ) -> Result<(), ReplayError>
+    where
+        F: FnMut(&mut Replay) -> Result<bool, ReplayError>,
+    {
+        let timeout = self.expect_timeout();
+        let deadline = std::time::Instant::now() + timeout;
+        let mut blocking = None;
+        let mut replay = self.replay.lock().unwrap();
+        loop {
+            let result = replay.as_mut().map_or(Ok(true), &mut turn);
+            match result {
+                Ok(true) => {
+                    self.replay_cond.notify_all();
+                    return Ok(());
+                }
+                Err(divergence) => {
+                    self.replay_cond.notify_all();
+                    return Err(divergence);
+                }
+                Ok(false) if blocking.is_none() => {
+                    // Scheduled callbacks may run while the thread starts
+                    // blocking, so the replay must not be locked.
+                    drop(replay);
+                    blocking = Some(
+                        self.notify_blocking(Blocker::Address(&self.replay as *const _ as usize)),
+                    );
+                    replay = self.replay.lock().unwrap();
+                }
+                Ok(false) => {
+                    let now = std::time::Instant::now();
+                    if now >= deadline {
+                        let divergence = replay.as_mut().unwrap().timeout(waiter, timeout);
+                        self.replay_cond.notify_all();
+                        return Err(divergence);
+                    }
+                    replay = self
+                        .replay_cond
+                        .wait_timeout(replay, deadline - now)
+                        .unwrap()
+                        .0;
+                }
+            }
         }
     }
 
+    /// Registers the given waker to be woken up as soon as the shared clock
+    /// reaches the given time, during which the current thread is in a timed
+    /// wait. Returns a handle to the waker, or `None` if the shared clock
+    /// already reached the given time, and the current shared time. Returns an
+    /// error if the current thread is not registered to the shared clock.
     pub fn register_timed_waker(
         &self,
         waker: Waker,
         timeout: Timepoint,
-    ) -> (Option<TimedWakerHandle>, Timepoint) {
-        let current_time = *self.time.lock().unwrap();
+    ) -> Result<(Option<TimedWakerHandle>, Timepoint), ClockError> {
+        let state = self.state.lock().unwrap();
+        let current_time = state.time;
         if current_time < timeout {
+            let guard = self.notify_timed_wait(current_time, timeout)?;
             let mut wakers = self.wakers.lock().unwrap();
-            let guard = self.notify_timed_wait();
             let result = TimedWakerHandle {
                 waker: Arc::new(waker),
                 guard,
@@ -160,40 +1287,121 @@ impl SharedClock {
             wakers.push(TimedWaker {
                 waker: Arc::downgrade(&result.waker),
                 timeout,
+                owner: thread::current().id(),
+                scheduled: None,
             });
-            (Some(result), current_time)
+            Ok((Some(result), current_time))
         } else {
-            (None, current_time)
+            Ok((None, current_time))
+        }
+    }
+
+    /// Registers the given waker to be woken up as soon as the shared clock
+    /// reaches the given time. In contrast to
+    /// [`register_timed_waker`](#method.register_timed_waker), the waker is
+    /// kept alive by the shared clock and the current thread does not enter a
+    /// timed wait. Returns the waker if the shared clock already reached the
+    /// given time.
+    pub fn schedule_waker(&self, waker: Waker, timeout: Timepoint) -> Option<Waker> {
+        let state = self.state.lock().unwrap();
+        if state.time < timeout {
+            let waker = Arc::new(waker);
+            self.wakers.lock().unwrap().push(TimedWaker {
+                waker: Arc::downgrade(&waker),
+                timeout,
+                owner: thread::current().id(),
+                scheduled: Some(waker),
+            });
+            None
+        } else {
+            Some(waker)
         }
     }
 }
 
-type TimedWaitData = RwLock<HashMap<ThreadId, (AtomicUsize, Mutex<()>, Condvar)>>;
+fn wake(wakers: Vec<Arc<Waker>>) {
+    for waker in wakers {
+        waker.wake_by_ref();
+    }
+}
 
-/// A RAII implementation for a timed wait. When this guard is dropped, the
-/// timed wait counter for the thread it was created on will be decreased.
-#[must_use = "if unused the timed wait state will be immediately reset"]
-pub struct TimedWaitGuard {
-    created_on: ThreadId,
-    data: Arc<TimedWaitData>,
+/// A RAII implementation for the registration of a thread to a shared clock.
+/// When this structure is dropped, the thread is no longer considered by the
+/// scheduler of the shared clock.
+pub struct ThreadRegistration {
+    shared_clock: Arc<SharedClock>,
+    id: ThreadId,
 }
 
-impl TimedWaitGuard {
-    pub fn new(data: Arc<TimedWaitData>) -> Self {
-        Self {
-            created_on: thread::current().id(),
-            data,
+impl Drop for ThreadRegistration {
+    fn drop(&mut self) {
+        self.shared_clock.unregister_thread(self.id);
+    }
+}
+
+/// A RAII implementation for a thread being blocked outside of a timed wait.
+/// When this guard is dropped, the thread is considered runnable again.
+#[must_use = "if unused the thread will immediately be considered runnable again"]
+pub struct BlockingGuard {
+    shared_clock: Arc<SharedClock>,
+    id: ThreadId,
+}
+
+impl Drop for BlockingGuard {
+    fn drop(&mut self) {
+        let mut state = self.shared_clock.state.lock().unwrap();
+        if let Some(blocker) = state.blocked.remove(&self.id) {
+            if let Some(wakeups) = state.wakeups.get_mut(&blocker) {
+                *wakeups -= 1;
+                if *wakeups == 0 {
+                    state.wakeups.remove(&blocker);
+                }
+            }
         }
     }
 }
 
+/// Information about a single timed wait of a thread.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TimedWaitInfo {
+    /// The time the thread waits for.
+    pub deadline: Timepoint,
+    /// The local time of the thread when it started waiting.
+    pub started_at: Timepoint,
+}
+
+/// The timed waits a thread has started so far.
+#[derive(Copy, Clone, Default)]
+struct TimedWaitHistory {
+    /// The number of timed waits the thread has started.
+    generation: u64,
+    /// The most recent timed wait of the thread.
+    last: Option<TimedWaitInfo>,
+}
+
+/// Per-thread data for notifying and waiting on timed waits.
+#[derive(Default)]
+struct ThreadTimedWaits {
+    /// The number of timed waits the thread is currently in.
+    active: AtomicUsize,
+    /// The timed waits the thread has started so far.
+    history: Mutex<TimedWaitHistory>,
+    /// Notified whenever the thread starts a timed wait.
+    started: Condvar,
+}
+
+type TimedWaitData = RwLock<HashMap<ThreadId, Arc<ThreadTimedWaits>>>;
+
+/// A RAII implementation for a timed wait. When this guard is dropped, the
+/// timed wait counter for the thread it was created on will be decreased.
+#[must_use = "if unused the timed wait state will be immediately reset"]
+pub struct TimedWaitGuard {
+    thread_info: Arc<ThreadTimedWaits>,
+}
+
 impl Drop for TimedWaitGuard {
     fn drop(&mut self) {
-        let lock = self.data.read().unwrap();
-        let thread_info = lock
-            .get(&self.created_on)
-            .expect("chronobreak internal error: thread was not registered");
-        thread_info.0.fetch_sub(1, Ordering::SeqCst);
+        self.thread_info.active.fetch_sub(1, Ordering::SeqCst);
     }
 }
 
@@ -201,17 +1409,23 @@ impl Drop for TimedWaitGuard {
 struct TimedWaker {
     waker: Weak<Waker>,
     timeout: Timepoint,
+    owner: ThreadId,
+    /// Keeps scheduled wakers alive, which are not owned by a
+    /// `TimedWakerHandle`.
+    scheduled: Option<Arc<Waker>>,
 }
 
+/// Timed wakers are ordered by their timeout in reverse, such that the
+/// waker with the earliest timeout is at the top of a `BinaryHeap`.
 impl Ord for TimedWaker {
     fn cmp(&self, rhs: &Self) -> std::cmp::Ordering {
-        self.timeout.cmp(&rhs.timeout)
+        rhs.timeout.cmp(&self.timeout)
     }
 }
 
 impl PartialOrd<TimedWaker> for TimedWaker {
     fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
-        self.timeout.partial_cmp(&rhs.timeout)
+        Some(self.cmp(rhs))
     }
 }
 
@@ -236,3 +1450,35 @@ impl TimedWakerHandle {
         (*self.waker).clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_register_while_expecting_a_timed_wait() {
+        let shared_clock = Arc::new(SharedClock::new(Config::default()));
+        let _registration = shared_clock.register_thread();
+        let main_thread = thread::current().id();
+        let thread_info = shared_clock.thread_timed_waits(main_thread).unwrap();
+        let waiter = {
+            let shared_clock = shared_clock.clone();
+            thread::spawn(move || {
+                shared_clock.expect_timed_wait_on(main_thread, Duration::from_secs(10))
+            })
+        };
+        // The waiter holds its own reference to the timed waits of the main
+        // thread once it waits for them.
+        while Arc::strong_count(&thread_info) < 3 {
+            thread::yield_now();
+        }
+        let registering = shared_clock.clone();
+        thread::spawn(move || drop(registering.register_thread()))
+            .join()
+            .unwrap();
+        let state = shared_clock.state.lock().unwrap();
+        let _timed_wait = shared_clock.notify_timed_wait(state.time, state.time);
+        drop(state);
+        assert! {waiter.join().unwrap().is_ok()};
+    }
+}
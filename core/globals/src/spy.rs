@@ -0,0 +1,113 @@
+use std::fmt;
+use std::panic::Location;
+use std::sync::{Arc, Mutex, Weak};
+use std::thread::ThreadId;
+use std::time::Duration;
+
+/// The kind of a requested wait.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WaitKind {
+    /// A sleep, e.g. by `thread::sleep`.
+    Sleep,
+    /// A `DelayFuture`, e.g. of a mocked timer, that has been created or
+    /// reset.
+    Delay,
+    /// A timed wait of a mocked blocking routine.
+    TimedWait,
+}
+
+impl fmt::Display for WaitKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Sleep => "sleep",
+            Self::Delay => "delay",
+            Self::TimedWait => "timed wait",
+        })
+    }
+}
+
+/// A wait requested by the code under test, recorded by a [`Spy`](struct.Spy.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RequestedWait {
+    /// The kind of the wait.
+    pub kind: WaitKind,
+    /// The requested duration.
+    pub duration: Duration,
+    /// The location of the caller that requested the wait.
+    pub location: &'static Location<'static>,
+    /// The thread that requested the wait.
+    pub thread: ThreadId,
+    /// The name of the thread, if any.
+    pub thread_name: Option<String>,
+}
+
+impl fmt::Display for RequestedWait {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} for {:?} at {} on thread '{}' ({:?})",
+            self.kind,
+            self.duration,
+            self.location,
+            self.thread_name.as_deref().unwrap_or("<unnamed>"),
+            self.thread
+        )
+    }
+}
+
+/// A handle returned by `clock::spy`, which records all waits requested by
+/// the threads registered to the shared clock while it is alive.
+#[derive(Clone, Debug, Default)]
+pub struct Spy {
+    waits: Arc<Mutex<Vec<RequestedWait>>>,
+}
+
+impl Spy {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn downgrade(&self) -> Weak<Mutex<Vec<RequestedWait>>> {
+        Arc::downgrade(&self.waits)
+    }
+
+    /// Returns all recorded waits in the order they were requested.
+    pub fn waits(&self) -> Vec<RequestedWait> {
+        self.waits.lock().unwrap().clone()
+    }
+
+    /// Returns the durations of all recorded waits in the order they were
+    /// requested.
+    pub fn durations(&self) -> Vec<Duration> {
+        let waits = self.waits.lock().unwrap();
+        waits.iter().map(|wait| wait.duration).collect()
+    }
+
+    /// Discards all recorded waits.
+    pub fn clear(&self) {
+        self.waits.lock().unwrap().clear()
+    }
+
+    /// Asserts that exactly the given durations have been requested to wait
+    /// for, in order, by sleeps, delays and timed waits.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the recorded durations differ from the given
+    /// ones. The panic message lists all recorded waits with their callers.
+    #[track_caller]
+    pub fn assert_sleeps(&self, expected: &[Duration]) {
+        let waits = self.waits();
+        let actual: Vec<_> = waits.iter().map(|wait| wait.duration).collect();
+        if actual != expected {
+            let mut message = format!(
+                "assertion failed: requested waits differ\n  expected: {:?}\n  actual:   {:?}\n  waits:",
+                expected, actual
+            );
+            for wait in &waits {
+                message.push_str(&format!("\n    {}", wait));
+            }
+            panic! {"{}", message};
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::ops;
+use std::thread::ThreadId;
+use std::time::Duration;
+
+/// Counters of the timing operations of a thread or of all threads.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// The number of sleeps, e.g. by `thread::sleep`.
+    pub sleeps: u64,
+    /// The total virtual time slept.
+    pub slept: Duration,
+    /// The number of explicit advances of the local clock, e.g. by a test
+    /// driving the clock through `clock::advance`.
+    pub advances: u64,
+    /// The total virtual time the local clock has explicitly been advanced
+    /// by.
+    pub advanced: Duration,
+    /// The number of waits on mocked `Condvar`s.
+    pub condvar_waits: u64,
+    /// The number of timed waits of mocked blocking routines, e.g. by
+    /// `thread::park_timeout` or `Condvar::wait_for`.
+    pub timed_waits: u64,
+    /// The number of polls of `DelayFuture`s, e.g. of mocked timers.
+    pub delay_polls: u64,
+    /// The number of times the local clock has been advanced by a
+    /// synchronization, e.g. when locking a mocked `Mutex` or joining a
+    /// thread.
+    pub sync_advances: u64,
+    /// The number of reads of the current time, e.g. by `Instant::now()`.
+    pub now_reads: u64,
+}
+
+impl ops::AddAssign for Stats {
+    fn add_assign(&mut self, other: Self) {
+        self.sleeps += other.sleeps;
+        self.slept += other.slept;
+        self.advances += other.advances;
+        self.advanced += other.advanced;
+        self.condvar_waits += other.condvar_waits;
+        self.timed_waits += other.timed_waits;
+        self.delay_polls += other.delay_polls;
+        self.sync_advances += other.sync_advances;
+        self.now_reads += other.now_reads;
+    }
+}
+
+/// The statistics of a single thread.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ThreadStats {
+    /// The thread.
+    pub thread: ThreadId,
+    /// The index of the thread in the order in which threads registered to
+    /// the shared clock.
+    pub thread_index: usize,
+    /// The name of the thread, if any.
+    pub thread_name: Option<String>,
+    /// The counters of the thread.
+    pub stats: Stats,
+}
+
+/// The statistics of all threads of a shared clock, returned by
+/// `clock::stats`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ClockStats {
+    threads: Vec<ThreadStats>,
+}
+
+impl ClockStats {
+    pub(crate) fn new(mut threads: Vec<ThreadStats>) -> Self {
+        threads.sort_by_key(|thread| thread.thread_index);
+        Self { threads }
+    }
+
+    /// Returns the statistics of all threads that performed any timing
+    /// operation, ordered by their index.
+    pub fn threads(&self) -> &[ThreadStats] {
+        &self.threads
+    }
+
+    /// Returns the counters of the given thread.
+    pub fn thread(&self, id: ThreadId) -> Stats {
+        self.threads
+            .iter()
+            .find(|thread| thread.thread == id)
+            .map(|thread| thread.stats)
+            .unwrap_or_default()
+    }
+
+    /// Returns the counters of all threads in total.
+    pub fn total(&self) -> Stats {
+        let mut total = Stats::default();
+        for thread in &self.threads {
+            total += thread.stats;
+        }
+        total
+    }
+}
+
+impl fmt::Display for ClockStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows: Vec<(String, Stats)> = self
+            .threads
+            .iter()
+            .map(|thread| {
+                let name = match &thread.thread_name {
+                    Some(name) => format!("#{} '{}'", thread.thread_index, name),
+                    None => format!("#{} ({:?})", thread.thread_index, thread.thread),
+                };
+                (name, thread.stats)
+            })
+            .collect();
+        rows.push(("total".to_string(), self.total()));
+        let width = rows
+            .iter()
+            .map(|(name, _)| name.len())
+            .fold("thread".len(), usize::max);
+        writeln!(
+            f,
+            "  {:<width$} {:>8} {:>14} {:>8} {:>14} {:>13} {:>11} {:>11} {:>13} {:>9}",
+            "thread",
+            "sleeps",
+            "slept",
+            "advances",
+            "advanced",
+            "condvar waits",
+            "timed waits",
+            "delay polls",
+            "sync advances",
+            "now reads",
+            width = width
+        )?;
+        for (name, stats) in rows {
+            writeln!(
+                f,
+                "  {:<width$} {:>8} {:>14} {:>8} {:>14} {:>13} {:>11} {:>11} {:>13} {:>9}",
+                name,
+                stats.sleeps,
+                format!("{:?}", stats.slept),
+                stats.advances,
+                format!("{:?}", stats.advanced),
+                stats.condvar_waits,
+                stats.timed_waits,
+                stats.delay_polls,
+                stats.sync_advances,
+                stats.now_reads,
+                width = width
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,108 @@
+use crate::shared_clock::Timepoint;
+use std::time::Duration;
+
+/// A linear mapping of the shared time to some derived time, which may be
+/// set to arbitrary values and advance at a different rate than the shared
+/// time.
+#[derive(Copy, Clone, Default)]
+pub struct TimeMapping {
+    /// The time at which the mapping has last been changed.
+    base: Timepoint,
+    /// The derived time at `base`.
+    mapped_base: Timepoint,
+    /// The deviation of the rate of the derived time from the rate of the
+    /// shared time in parts per million.
+    ppm: i64,
+}
+
+impl TimeMapping {
+    /// Returns a mapping that starts at the given derived time.
+    pub fn starting_at(mapped_base: Timepoint) -> Self {
+        Self {
+            mapped_base,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the derived time that corresponds to the given time.
+    pub fn map(&self, time: Timepoint) -> Timepoint {
+        if time >= self.base {
+            self.mapped_base + self.scale(time.duration_since(self.base))
+        } else {
+            self.mapped_base
+                .checked_sub(self.scale(self.base.duration_since(time)))
+                .unwrap_or(Timepoint::START)
+        }
+    }
+
+    /// Sets the derived time at the given time.
+    pub fn set(&mut self, time: Timepoint, mapped: Timepoint) {
+        self.base = time;
+        self.mapped_base = mapped;
+    }
+
+    /// Changes the rate of the derived time from the given time on.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if `ppm` is not greater than -1,000,000, which
+    /// would stop the derived time or make it run backwards.
+    pub fn set_rate(&mut self, time: Timepoint, ppm: i64) {
+        assert! {ppm > -1_000_000, "the rate of a clock may not be changed by {} ppm", ppm};
+        self.set(time, self.map(time));
+        self.ppm = ppm;
+    }
+
+    fn scale(&self, dur: Duration) -> Duration {
+        let nanos = dur.as_nanos() * (1_000_000 + self.ppm) as u128 / 1_000_000;
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
+/// Constant offset of a thread's clock from the shared clock.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Skew {
+    /// The thread's clock is ahead of the shared clock.
+    Ahead(Duration),
+    /// The thread's clock is behind the shared clock. The resulting time
+    /// saturates at [`Timepoint::START`](../shared_clock/struct.Timepoint.html#associatedconstant.START).
+    Behind(Duration),
+}
+
+impl Default for Skew {
+    fn default() -> Self {
+        Self::Ahead(Duration::default())
+    }
+}
+
+impl Skew {
+    pub fn apply(&self, time: Timepoint) -> Timepoint {
+        match self {
+            Self::Ahead(dur) => time + *dur,
+            Self::Behind(dur) => time.checked_sub(*dur).unwrap_or(Timepoint::START),
+        }
+    }
+}
+
+/// Skew and drift of a single thread's clock.
+#[derive(Copy, Clone, Default)]
+pub struct ThreadClock {
+    pub skew: Skew,
+    pub drift: TimeMapping,
+}
+
+impl ThreadClock {
+    /// Returns the time the thread observes at the given time. The drift is
+    /// applied to the given time, which is then converted by `derive`, e.g.
+    /// offset to the start of mocked `Instant`s. The skew is applied last,
+    /// such that it does not saturate before the conversion.
+    pub fn map<F>(&self, time: Timepoint, derive: F) -> Timepoint
+    where
+        F: FnOnce(Timepoint) -> Timepoint,
+    {
+        self.skew.apply(derive(self.drift.map(time)))
+    }
+}
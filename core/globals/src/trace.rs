@@ -0,0 +1,265 @@
+use crate::shared_clock::Timepoint;
+use std::fmt;
+use std::thread::ThreadId;
+use std::time::Duration;
+
+#[cfg(feature = "chrome-trace")]
+mod chrome;
+
+/// The kind of a recorded clock event.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum TraceEventKind {
+    /// The thread slept until the given time, e.g. by `thread::sleep`.
+    Sleep { to: Timepoint },
+    /// The thread explicitly advanced its local clock to the given time, e.g.
+    /// by `clock::advance`.
+    Advance { to: Timepoint },
+    /// The thread synchronized its local clock with another thread to the
+    /// given time, e.g. when locking a mocked `Mutex` or joining a thread.
+    Sync { to: Timepoint },
+    /// The thread registered a timer, e.g. of a pending `DelayFuture`.
+    TimerRegistered { deadline: Timepoint },
+    /// A timer of the thread fired and its `DelayFuture` became ready.
+    TimerFired { deadline: Timepoint },
+    /// The thread started a timed wait of a mocked blocking routine until the
+    /// given deadline, e.g. by `thread::park_timeout`.
+    TimedWait { deadline: Timepoint },
+    /// The clock has been frozen on the thread.
+    Freeze,
+    /// The clock has been unfrozen on the thread.
+    Unfreeze,
+}
+
+impl TraceEventKind {
+    /// Returns the short name of the event kind.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sleep { .. } => "sleep",
+            Self::Advance { .. } => "advance",
+            Self::Sync { .. } => "sync",
+            Self::TimerRegistered { .. } => "timer registered",
+            Self::TimerFired { .. } => "timer fired",
+            Self::TimedWait { .. } => "timed wait",
+            Self::Freeze => "freeze",
+            Self::Unfreeze => "unfreeze",
+        }
+    }
+
+    /// Returns the local time the event advanced the thread's clock to, if
+    /// any.
+    pub fn target(&self) -> Option<Timepoint> {
+        match self {
+            Self::Sleep { to } | Self::Advance { to } | Self::Sync { to } => Some(*to),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TraceEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Sleep { to } | Self::Advance { to } | Self::Sync { to } => {
+                write!(f, "{} to {:?}", self.name(), to)
+            }
+            Self::TimerRegistered { deadline }
+            | Self::TimerFired { deadline }
+            | Self::TimedWait { deadline } => {
+                write!(f, "{} for {:?}", self.name(), deadline)
+            }
+            Self::Freeze | Self::Unfreeze => f.write_str(self.name()),
+        }
+    }
+}
+
+/// A single recorded clock event.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TraceEvent {
+    /// The kind of the event.
+    pub kind: TraceEventKind,
+    /// The thread on which the event occurred. None for events of a trace
+    /// that has been [parsed](struct.Trace.html#method.from_chrome_json).
+    pub thread: Option<ThreadId>,
+    /// The index of the thread in the order in which threads registered to
+    /// the shared clock, which identifies the thread across runs.
+    pub thread_index: usize,
+    /// The name of the thread, if any.
+    pub thread_name: Option<String>,
+    /// The local time of the thread when the event started.
+    pub local_time: Timepoint,
+    /// The real time since the start of the trace when the event started.
+    pub real_time: Duration,
+    /// The real time the event took, e.g. while waiting for a frozen clock.
+    pub real_duration: Duration,
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "thread #{}", self.thread_index)?;
+        if let Some(name) = &self.thread_name {
+            write!(f, " '{}'", name)?;
+        }
+        write!(f, ": {} at local time {:?}", self.kind, self.local_time)
+    }
+}
+
+/// A recording of clock events, started by `clock::start_trace`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    pub(crate) fn new(events: Vec<TraceEvent>) -> Self {
+        Self { events }
+    }
+
+    /// Returns all recorded events in the order they were recorded.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+}
+
+/// Error returned if a run does not follow the trace it replays.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReplayError {
+    /// The event with the given number differs from the recorded event.
+    Diverged {
+        /// The number of the event within the trace, starting at 1.
+        event: usize,
+        /// Description of the recorded event.
+        expected: String,
+        /// Description of what happened instead.
+        actual: String,
+    },
+    /// The run ended before all recorded events were replayed.
+    Incomplete {
+        /// The number of replayed events.
+        replayed: usize,
+        /// The number of recorded events.
+        recorded: usize,
+        /// Description of the next recorded event.
+        next: String,
+    },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Diverged {
+                event,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "event {} diverged from the recorded trace: expected {}, but {}",
+                event, expected, actual
+            ),
+            Self::Incomplete {
+                replayed,
+                recorded,
+                next,
+            } => write!(
+                f,
+                "the run ended after {} of {} recorded events; the next recorded event is {}",
+                replayed, recorded, next
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Progress of a run that replays a trace.
+pub(crate) struct Replay {
+    events: Vec<TraceEvent>,
+    next: usize,
+    divergence: Option<ReplayError>,
+}
+
+impl Replay {
+    pub(crate) fn new(trace: &Trace) -> Self {
+        Self {
+            events: trace.events.clone(),
+            next: 0,
+            divergence: None,
+        }
+    }
+
+    /// Checks whether it is the turn of the given event. Returns true and
+    /// moves on to the next event if so, false if the event of another
+    /// thread is due first and an error if the run diverged. Once the run
+    /// diverged, all events are let through.
+    pub(crate) fn turn(&mut self, event: &TraceEvent) -> Result<bool, ReplayError> {
+        if self.divergence.is_some() {
+            return Ok(true);
+        }
+        let expected = match self.events.get(self.next) {
+            Some(expected) if expected.thread_index != event.thread_index => return Ok(false),
+            Some(expected) => expected,
+            None => {
+                return Err(
+                    self.diverge("the end of the trace".to_string(), format!("got {}", event))
+                )
+            }
+        };
+        if expected.kind == event.kind && expected.local_time == event.local_time {
+            self.next += 1;
+            Ok(true)
+        } else {
+            let expected = expected.to_string();
+            Err(self.diverge(expected, format!("got {}", event)))
+        }
+    }
+
+    /// Checks whether the next event of the trace belongs to the thread with
+    /// the given index, i.e. whether the thread may cause its next event.
+    /// Once the run diverged or all events have been replayed, all threads
+    /// may proceed.
+    pub(crate) fn is_turn_of(&self, thread_index: usize) -> bool {
+        self.divergence.is_some()
+            || self
+                .events
+                .get(self.next)
+                .is_none_or(|next| next.thread_index == thread_index)
+    }
+
+    /// Ends the replay because the given waiter, e.g. an event, did not get
+    /// its turn within the given real time.
+    pub(crate) fn timeout(&mut self, waiter: &dyn fmt::Display, timeout: Duration) -> ReplayError {
+        if let Some(divergence) = &self.divergence {
+            return divergence.clone();
+        }
+        let expected = self.events[self.next].to_string();
+        self.diverge(
+            expected,
+            format!(
+                "it did not occur within {:?} of real time, while {} waited for its turn",
+                timeout, waiter
+            ),
+        )
+    }
+
+    fn diverge(&mut self, expected: String, actual: String) -> ReplayError {
+        let divergence = ReplayError::Diverged {
+            event: self.next + 1,
+            expected,
+            actual,
+        };
+        self.divergence = Some(divergence.clone());
+        divergence
+    }
+
+    /// Returns the first divergence or an error if not all events have been
+    /// replayed.
+    pub(crate) fn finish(self) -> Result<(), ReplayError> {
+        match (self.divergence, self.events.get(self.next)) {
+            (Some(divergence), _) => Err(divergence),
+            (None, Some(next)) => Err(ReplayError::Incomplete {
+                replayed: self.next,
+                recorded: self.events.len(),
+                next: next.to_string(),
+            }),
+            (None, None) => Ok(()),
+        }
+    }
+}
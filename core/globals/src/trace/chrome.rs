@@ -0,0 +1,214 @@
+use super::{Trace, TraceEvent, TraceEventKind};
+use crate::shared_clock::Timepoint;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+impl Trace {
+    /// Exports the trace in the Chrome trace event format, which can be opened
+    /// in e.g. Perfetto or `chrome://tracing`. Events are placed on the
+    /// virtual timeline of their thread, while real timestamps are included as
+    /// arguments.
+    pub fn to_chrome_json(&self) -> String {
+        let mut events = Vec::new();
+        let mut threads: Vec<&TraceEvent> = Vec::new();
+        for event in &self.events {
+            if !threads.iter().any(|e| e.thread_index == event.thread_index) {
+                threads.push(event);
+            }
+        }
+        for event in threads {
+            let name = match (&event.thread_name, event.thread) {
+                (Some(name), _) => name.to_string(),
+                (None, Some(thread)) => format!("{:?}", thread),
+                (None, None) => format!("thread #{}", event.thread_index),
+            };
+            events.push(json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": 1,
+                "tid": event.thread_index,
+                "args": {"name": name},
+            }));
+        }
+        for event in &self.events {
+            let ts = event.local_time.saturating_duration_since(Timepoint::START);
+            let mut args = json!({
+                "real_time_us": micros(event.real_time),
+                "real_duration_us": micros(event.real_duration),
+                "local_time_ns": nanos(event.local_time),
+            });
+            let mut json = json!({
+                "name": event.kind.name(),
+                "cat": "clock",
+                "ts": micros(ts),
+                "pid": 1,
+                "tid": event.thread_index,
+            });
+            match event.kind {
+                TraceEventKind::Sleep { to }
+                | TraceEventKind::Advance { to }
+                | TraceEventKind::Sync { to } => {
+                    args["to_ns"] = nanos(to).into();
+                    json["ph"] = "X".into();
+                    json["dur"] = micros(to.saturating_duration_since(event.local_time)).into();
+                }
+                TraceEventKind::TimerRegistered { deadline }
+                | TraceEventKind::TimerFired { deadline }
+                | TraceEventKind::TimedWait { deadline } => {
+                    args["deadline_ns"] = nanos(deadline).into();
+                    json["ph"] = "i".into();
+                    json["s"] = "t".into();
+                }
+                TraceEventKind::Freeze | TraceEventKind::Unfreeze => {
+                    json["ph"] = "i".into();
+                    json["s"] = "t".into();
+                }
+            }
+            json["args"] = args;
+            events.push(json);
+        }
+        let trace = json!({"displayTimeUnit": "ms", "traceEvents": events});
+        serde_json::to_string(&trace).unwrap()
+    }
+
+    /// Writes the trace in the Chrome trace event format to the given file.
+    /// See [`to_chrome_json`](#method.to_chrome_json).
+    pub fn write_chrome_json<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_chrome_json())
+    }
+
+    /// Parses a trace exported by [`to_chrome_json`](#method.to_chrome_json),
+    /// e.g. to [replay](../../chronobreak/clock/fn.replay.html) it. The JSON
+    /// may be reformatted, e.g. pretty-printed, but other Chrome trace event
+    /// files are not supported. Returns an error naming the first event that
+    /// does not match the exported layout.
+    pub fn from_chrome_json(json: &str) -> Result<Self, String> {
+        let root: Value =
+            serde_json::from_str(json).map_err(|err| format!("invalid JSON: {}", err))?;
+        let trace_events = root
+            .get("traceEvents")
+            .and_then(Value::as_array)
+            .ok_or("missing or invalid field `traceEvents`")?;
+        let mut names = HashMap::new();
+        let mut events = Vec::new();
+        for (index, value) in trace_events.iter().enumerate() {
+            let event = ChromeEvent { index, value };
+            match (event.str("ph")?, value.get("cat").and_then(Value::as_str)) {
+                ("M", _) if event.str("name")? == "thread_name" => {
+                    names.insert(event.u64("tid")? as usize, event.str("args.name")?);
+                }
+                ("X", Some("clock")) | ("i", Some("clock")) => {
+                    let name = event.str("name")?;
+                    let time = match name {
+                        "sleep" | "advance" | "sync" => Some(event.nanos("args.to_ns")?),
+                        "timer registered" | "timer fired" | "timed wait" => {
+                            Some(event.nanos("args.deadline_ns")?)
+                        }
+                        _ => None,
+                    };
+                    let kind = TraceEventKind::from_name(name, time)
+                        .ok_or_else(|| event.error(&format!("unknown event `{}`", name)))?;
+                    events.push(TraceEvent {
+                        kind,
+                        thread: None,
+                        thread_index: event.u64("tid")? as usize,
+                        thread_name: None,
+                        local_time: event.nanos("args.local_time_ns")?,
+                        real_time: event.micros("args.real_time_us")?,
+                        real_duration: event.micros("args.real_duration_us")?,
+                    });
+                }
+                _ => return Err(event.error("unsupported trace event")),
+            }
+        }
+        for event in &mut events {
+            event.thread_name = names
+                .get(&event.thread_index)
+                .filter(|name| **name != format!("thread #{}", event.thread_index))
+                .filter(|name| !name.starts_with("ThreadId("))
+                .map(|name| name.to_string());
+        }
+        Ok(Self::new(events))
+    }
+}
+
+impl TraceEventKind {
+    fn from_name(name: &str, time: Option<Timepoint>) -> Option<Self> {
+        Some(match (name, time) {
+            ("sleep", Some(to)) => Self::Sleep { to },
+            ("advance", Some(to)) => Self::Advance { to },
+            ("sync", Some(to)) => Self::Sync { to },
+            ("timer registered", Some(deadline)) => Self::TimerRegistered { deadline },
+            ("timer fired", Some(deadline)) => Self::TimerFired { deadline },
+            ("timed wait", Some(deadline)) => Self::TimedWait { deadline },
+            ("freeze", None) => Self::Freeze,
+            ("unfreeze", None) => Self::Unfreeze,
+            _ => return None,
+        })
+    }
+}
+
+/// An event of a Chrome trace event file that is being parsed.
+struct ChromeEvent<'a> {
+    index: usize,
+    value: &'a Value,
+}
+
+impl<'a> ChromeEvent<'a> {
+    fn error(&self, message: &str) -> String {
+        format!("trace event {}: {}", self.index + 1, message)
+    }
+
+    /// Returns the field with the given dot-separated path.
+    fn field<T, F>(&self, path: &str, convert: F) -> Result<T, String>
+    where
+        F: FnOnce(&'a Value) -> Option<T>,
+    {
+        path.split('.')
+            .try_fold(self.value, |value, key| value.get(key))
+            .and_then(convert)
+            .ok_or_else(|| self.error(&format!("missing or invalid field `{}`", path)))
+    }
+
+    fn str(&self, path: &str) -> Result<&'a str, String> {
+        self.field(path, Value::as_str)
+    }
+
+    fn u64(&self, path: &str) -> Result<u64, String> {
+        self.field(path, Value::as_u64)
+    }
+
+    /// Returns the time of a field in nanoseconds since
+    /// [`Timepoint::START`](../struct.Timepoint.html#associatedconstant.START).
+    fn nanos(&self, path: &str) -> Result<Timepoint, String> {
+        Ok(Timepoint::START + Duration::from_nanos(self.u64(path)?))
+    }
+
+    /// Returns the duration of a field in microseconds with nanosecond
+    /// precision.
+    fn micros(&self, path: &str) -> Result<Duration, String> {
+        self.field(path, |value| {
+            let nanos = (value.as_f64()? * 1_000.0).round();
+            if nanos >= 0.0 && nanos < u64::MAX as f64 {
+                Some(Duration::from_nanos(nanos as u64))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Returns the given duration in microseconds with nanosecond precision.
+fn micros(dur: Duration) -> f64 {
+    dur.as_nanos() as f64 / 1_000.0
+}
+
+/// Returns the given time in nanoseconds since
+/// [`Timepoint::START`](../struct.Timepoint.html#associatedconstant.START).
+fn nanos(time: Timepoint) -> u64 {
+    time.saturating_duration_since(Timepoint::START).as_nanos() as u64
+}
@@ -0,0 +1,280 @@
+use crate::clock;
+use std::fmt;
+use std::ops;
+use std::time::{self, Duration};
+
+/// The tolerance of the assertion macros if the clock is not mocked and no
+/// tolerance is given explicitly.
+pub const DEFAULT_TOLERANCE: Duration = Duration::from_millis(100);
+
+/// A point in time that can be checked by the assertion macros, e.g. a mocked
+/// or an actual `Instant` or `SystemTime`.
+pub trait Timestamp: Copy + fmt::Debug + ops::Add<Duration, Output = Self> {
+    /// Returns the current point in time.
+    fn now() -> Self;
+
+    /// Returns the duration from `earlier` to `self`, or `None` if `earlier`
+    /// is later than `self`.
+    fn checked_duration_since(&self, earlier: Self) -> Option<Duration>;
+}
+
+impl Timestamp for time::Instant {
+    fn now() -> Self {
+        Self::now()
+    }
+
+    fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.checked_duration_since(earlier)
+    }
+}
+
+impl Timestamp for time::SystemTime {
+    fn now() -> Self {
+        Self::now()
+    }
+
+    fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.duration_since(earlier).ok()
+    }
+}
+
+/// A signed duration.
+#[derive(Copy, Clone)]
+enum Delta {
+    Negative(Duration),
+    Positive(Duration),
+}
+
+impl Delta {
+    fn between<T: Timestamp>(earlier: T, later: T) -> Self {
+        match later.checked_duration_since(earlier) {
+            Some(dur) => Self::Positive(dur),
+            None => Self::Negative(earlier.checked_duration_since(later).unwrap_or_default()),
+        }
+    }
+
+    fn of(actual: Duration, expected: Duration) -> Self {
+        match actual.checked_sub(expected) {
+            Some(dur) => Self::Positive(dur),
+            None => Self::Negative(expected - actual),
+        }
+    }
+
+    fn abs(&self) -> Duration {
+        match self {
+            Self::Negative(dur) | Self::Positive(dur) => *dur,
+        }
+    }
+}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Negative(dur) => write!(f, "-{:?}", dur),
+            Self::Positive(dur) => write!(f, "+{:?}", dur),
+        }
+    }
+}
+
+fn tolerance(tolerance: Option<Duration>) -> Duration {
+    match tolerance {
+        Some(tolerance) => tolerance,
+        None if clock::is_mocked() => Duration::default(),
+        None => DEFAULT_TOLERANCE,
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_elapsed<T: Timestamp>(
+    start: T,
+    expected: Duration,
+    tolerance: Option<Duration>,
+    assertion: &str,
+) {
+    let now = T::now();
+    let tolerance = self::tolerance(tolerance);
+    let actual = Delta::between(start, now);
+    let delta = match actual {
+        Delta::Positive(actual) => Delta::of(actual, expected),
+        Delta::Negative(actual) => Delta::Negative(expected + actual),
+    };
+    if !matches!(delta, Delta::Positive(delta) if delta <= tolerance) {
+        panic! {
+            "assertion failed: `{}`\n  start:     {:?}\n  expected:  {:?} (start +{:?})\n  actual:    {:?} (start {})\n  delta:     {}\n  tolerance: {:?}",
+            assertion, start, start + expected, expected, now, actual, delta, tolerance
+        };
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_now<T: Timestamp>(expected: T, tolerance: Option<Duration>, assertion: &str) {
+    let now = T::now();
+    let tolerance = self::tolerance(tolerance);
+    let delta = Delta::between(expected, now);
+    if delta.abs() > tolerance {
+        panic! {
+            "assertion failed: `{}`\n  expected:  {:?}\n  actual:    {:?}\n  delta:     {}\n  tolerance: {:?}",
+            assertion, expected, now, delta, tolerance
+        };
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_between<T: Timestamp>(
+    start: T,
+    min: Duration,
+    max: Duration,
+    tolerance: Option<Duration>,
+    assertion: &str,
+) {
+    let now = T::now();
+    let tolerance = self::tolerance(tolerance);
+    let actual = Delta::between(start, now);
+    let in_range =
+        matches!(actual, Delta::Positive(actual) if min <= actual && actual <= max + tolerance);
+    if !in_range {
+        panic! {
+            "assertion failed: `{}`\n  start:     {:?}\n  expected:  {:?} ..= {:?} (start +{:?} ..= start +{:?})\n  actual:    {:?} (start {})\n  tolerance: {:?}",
+            assertion, start, start + min, start + max, min, max, now, actual, tolerance
+        };
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_no_time_passed<F, R>(f: F, tolerance: Option<Duration>, assertion: &str) -> R
+where
+    F: FnOnce() -> R,
+{
+    let tolerance = self::tolerance(tolerance);
+    let (start, result, end) = if clock::is_mocked() {
+        let start = clock::get();
+        let result = f();
+        (start, result, clock::get())
+    } else {
+        let start = time::Instant::now();
+        let result = f();
+        let elapsed = start.elapsed();
+        (
+            clock::Timepoint::START,
+            result,
+            clock::Timepoint::START + elapsed,
+        )
+    };
+    let elapsed = end.duration_since(start);
+    if elapsed > tolerance {
+        panic! {
+            "assertion failed: `{}`\n  start:     {:?}\n  end:       {:?}\n  delta:     +{:?}\n  tolerance: {:?}",
+            assertion, start, end, elapsed, tolerance
+        };
+    }
+    result
+}
+
+/// Asserts that exactly the given duration has passed since `start`, which
+/// may be a mocked or an actual `Instant` or `SystemTime`. If the clock is not
+/// mocked, up to [`DEFAULT_TOLERANCE`](assert/constant.DEFAULT_TOLERANCE.html)
+/// more may have passed, unless a tolerance is given as third argument.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::{assert_elapsed, chronobreak};
+/// #[chronobreak]
+/// use std::time::*;
+/// #[chronobreak]
+/// use std::thread;
+///
+/// #[chronobreak::test]
+/// fn sleep_for_a_second() {
+///     let start = Instant::now();
+///     thread::sleep(Duration::from_secs(1));
+///     assert_elapsed!(start, Duration::from_secs(1));
+/// }
+/// # fn main() {}
+/// ```
+#[macro_export]
+macro_rules! assert_elapsed {
+    ($start:expr, $expected:expr $(,)?) => {
+        $crate::assert::assert_elapsed(
+            $start,
+            $expected,
+            None,
+            stringify!(assert_elapsed!($start, $expected)),
+        )
+    };
+    ($start:expr, $expected:expr, $tolerance:expr $(,)?) => {
+        $crate::assert::assert_elapsed(
+            $start,
+            $expected,
+            Some($tolerance),
+            stringify!(assert_elapsed!($start, $expected, $tolerance)),
+        )
+    };
+}
+
+/// Asserts that the current time equals the given mocked or actual `Instant`
+/// or `SystemTime`. If the clock is not mocked, the current time may differ by
+/// up to [`DEFAULT_TOLERANCE`](assert/constant.DEFAULT_TOLERANCE.html),
+/// unless a tolerance is given as second argument.
+#[macro_export]
+macro_rules! assert_now {
+    ($expected:expr $(,)?) => {
+        $crate::assert::assert_now($expected, None, stringify!(assert_now!($expected)))
+    };
+    ($expected:expr, $tolerance:expr $(,)?) => {
+        $crate::assert::assert_now(
+            $expected,
+            Some($tolerance),
+            stringify!(assert_now!($expected, $tolerance)),
+        )
+    };
+}
+
+/// Asserts that at least `min` and at most `max` have passed since `start`,
+/// which may be a mocked or an actual `Instant` or `SystemTime`. If the clock
+/// is not mocked, up to [`DEFAULT_TOLERANCE`](assert/constant.DEFAULT_TOLERANCE.html)
+/// more than `max` may have passed, unless a tolerance is given as fourth
+/// argument.
+#[macro_export]
+macro_rules! assert_between {
+    ($start:expr, $min:expr, $max:expr $(,)?) => {
+        $crate::assert::assert_between(
+            $start,
+            $min,
+            $max,
+            None,
+            stringify!(assert_between!($start, $min, $max)),
+        )
+    };
+    ($start:expr, $min:expr, $max:expr, $tolerance:expr $(,)?) => {
+        $crate::assert::assert_between(
+            $start,
+            $min,
+            $max,
+            Some($tolerance),
+            stringify!(assert_between!($start, $min, $max, $tolerance)),
+        )
+    };
+}
+
+/// Asserts that no time passes while the given closure runs and returns its
+/// result. If the clock is not mocked, up to
+/// [`DEFAULT_TOLERANCE`](assert/constant.DEFAULT_TOLERANCE.html) may pass,
+/// unless a tolerance is given as second argument.
+#[macro_export]
+macro_rules! assert_no_time_passed {
+    ($f:expr $(,)?) => {
+        $crate::assert::assert_no_time_passed($f, None, stringify!(assert_no_time_passed!($f)))
+    };
+    ($f:expr, $tolerance:expr $(,)?) => {
+        $crate::assert::assert_no_time_passed(
+            $f,
+            Some($tolerance),
+            stringify!(assert_no_time_passed!($f, $tolerance)),
+        )
+    };
+}
@@ -1,12 +1,47 @@
 use chronobreak_globals::local_clock::{LocalClock, STATE};
-use chronobreak_globals::shared_clock::TimedWakerHandle;
+use chronobreak_globals::shared_clock::{self, SharedClock, TimedWakerHandle};
+use std::env;
+use std::fmt;
 use std::future::Future;
+use std::panic::{self, AssertUnwindSafe, Location};
 use std::pin::Pin;
-use std::task::{Context, Poll};
-use std::thread::ThreadId;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
 
-pub use chronobreak_globals::shared_clock::Timepoint;
+pub use chronobreak_globals::jitter::{Distribution, JITTER_SEED_VAR};
+pub use chronobreak_globals::shared_clock::{
+    Blocker, ClockError, Config, ExpectTimeout, PendingTimer, Skew, TimedWaitInfo, TimedWaiter,
+    Timepoint, DEFAULT_INSTANT_START,
+};
+pub use chronobreak_globals::spy::{RequestedWait, Spy, WaitKind};
+pub use chronobreak_globals::stats::{ClockStats, Stats, ThreadStats};
+pub use chronobreak_globals::trace::{ReplayError, Trace, TraceEvent, TraceEventKind};
+
+/// Calls the given function with the local clock of the current thread.
+fn try_with_local_clock<F, R>(f: F) -> Result<R, ClockError>
+where
+    F: FnOnce(&LocalClock) -> R,
+{
+    STATE.with(|state| state.borrow().as_ref().map(f).ok_or(ClockError::NotMocked))
+}
+
+/// Calls the given function with the mutable local clock of the current
+/// thread.
+fn try_with_local_clock_mut<F, R>(f: F) -> Result<R, ClockError>
+where
+    F: FnOnce(&mut LocalClock) -> R,
+{
+    STATE.with(|state| {
+        state
+            .borrow_mut()
+            .as_mut()
+            .map(f)
+            .ok_or(ClockError::NotMocked)
+    })
+}
 
 /// A RAII guard returned by [`mock`](fn.mock.html). When this structure is
 /// dropped, the mocked clock is destroyed.
@@ -15,9 +50,20 @@ pub struct ClockGuard {}
 
 impl Drop for ClockGuard {
     fn drop(&mut self) {
-        STATE.with(|state| {
-            *state.borrow_mut() = None;
-        });
+        let local_clock = STATE.with(|state| state.borrow_mut().take());
+        if let Some(local_clock) = &local_clock {
+            let shared_clock = &local_clock.shared_clock;
+            if thread::panicking() && shared_clock.print_stats_on_failure() {
+                eprintln!("chronobreak clock statistics:\n{}", shared_clock.stats());
+            }
+            if let (true, Some(seed)) = (thread::panicking(), shared_clock.wait_jitter_seed()) {
+                eprintln!(
+                    "chronobreak wait jitter seed: {} (set {}={} to reproduce)",
+                    seed, JITTER_SEED_VAR, seed
+                );
+            }
+        }
+        drop(local_clock);
     }
 }
 
@@ -48,33 +94,69 @@ impl Drop for UnfreezeGuard {
 /// thread advances the shared clock past the time of creation of this instance
 /// plus the given delay. If the shared clock has already passed this time,
 /// the future will be ready immediately.
+///
+/// # Scheduled behaviour
+///
+/// When polled, given waker will be registered like for a frozen clock. The
+/// [`scheduled`](fn.scheduled.html) clock advances to the earliest timeout of
+/// all registered wakers only once all registered threads are blocked, e.g.
+/// when the executor polling this future has no more runnable work. See
+/// [`block_on`](fn.block_on.html).
 pub struct DelayFuture {
     timeout: Timepoint,
     waker_handle: Option<TimedWakerHandle>,
+    location: &'static Location<'static>,
 }
 
 impl DelayFuture {
+    #[track_caller]
     pub fn new(delay: Duration) -> Self {
-        Self {
-            timeout: get() + delay,
-            waker_handle: None,
+        match Self::try_new(delay) {
+            Ok(delay) => delay,
+            Err(ClockError::NotMocked) => {
+                panic! {"chronobreak::DelayFuture::new requires the clock to be mocked"}
+            }
+            Err(error) => panic! {"chronobreak::DelayFuture::new failed: {}", error},
         }
     }
 
+    #[track_caller]
+    pub fn try_new(delay: Duration) -> Result<Self, ClockError> {
+        let timeout = try_get()? + delay + wait_jitter();
+        spy_wait(WaitKind::Delay, delay, Location::caller());
+        Ok(Self {
+            timeout,
+            waker_handle: None,
+            location: Location::caller(),
+        })
+    }
+
+    #[track_caller]
     pub fn reset(&mut self, delay: Duration) {
-        STATE.with(|state| {
-            let state = state.borrow();
-            let shared_clock = &state
-                .as_ref()
-                .expect("chronobreak::DelayFuture::poll requires the clock to be mocked")
-                .shared_clock;
-            self.timeout = get() + delay;
-            if let Some(handle) = self.waker_handle.take() {
-                self.waker_handle = shared_clock
-                    .register_timed_waker(handle.waker(), self.timeout)
-                    .0;
+        match self.try_reset(delay) {
+            Ok(()) => {}
+            Err(ClockError::NotMocked) => {
+                panic! {"chronobreak::DelayFuture::reset requires the clock to be mocked"}
             }
-        })
+            Err(error) => panic! {"chronobreak::DelayFuture::reset failed: {}", error},
+        }
+    }
+
+    #[track_caller]
+    pub fn try_reset(&mut self, delay: Duration) -> Result<(), ClockError> {
+        let timeout = try_get()? + delay + wait_jitter();
+        spy_wait(WaitKind::Delay, delay, Location::caller());
+        self.timeout = timeout;
+        self.location = Location::caller();
+        if let Some(handle) = self.waker_handle.take() {
+            self.waker_handle = try_with_local_clock(|state| {
+                state
+                    .shared_clock
+                    .register_timed_waker(handle.waker(), timeout)
+            })??
+            .0;
+        }
+        Ok(())
     }
 }
 
@@ -82,11 +164,31 @@ impl Future for DelayFuture {
     type Output = ();
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
-        if !is_frozen() {
+        count(|stats| stats.delay_polls += 1);
+        let started = Instant::now();
+        let registered = self.waker_handle.is_some();
+        if !registered {
+            check_limits(self.timeout, self.location, true);
+        }
+        if !is_frozen() && !is_scheduled() {
+            let local_time = get();
+            await_turn();
             advance_to(self.timeout);
+            record(
+                TraceEventKind::TimerFired {
+                    deadline: self.timeout,
+                },
+                local_time,
+                started,
+            );
             return Poll::Ready(());
         }
-        let (handle, current_time) = STATE.with(|state| {
+        if !registered {
+            // Polls of a registered timer only record an event once it fired,
+            // which the shared clock has been advanced for in turn.
+            await_turn();
+        }
+        let result = STATE.with(|state| {
             let state = state.borrow();
             let shared_clock = &state
                 .as_ref()
@@ -94,13 +196,22 @@ impl Future for DelayFuture {
                 .shared_clock;
             shared_clock.register_timed_waker(cx.waker().clone(), self.timeout)
         });
+        let (handle, current_time) = match result {
+            Ok(registered) => registered,
+            Err(error) => panic! {"chronobreak::DelayFuture::poll failed: {}", error},
+        };
         let this = Pin::into_inner(self);
         this.waker_handle = handle;
         let _guard = unfreeze_scoped();
         advance_to(current_time);
+        let deadline = this.timeout;
         if this.waker_handle.is_some() {
+            if !registered {
+                record(TraceEventKind::TimerRegistered { deadline }, get(), started);
+            }
             Poll::Pending
         } else {
+            record(TraceEventKind::TimerFired { deadline }, get(), started);
             Poll::Ready(())
         }
     }
@@ -120,32 +231,222 @@ pub fn is_mocked() -> bool {
 ///
 /// This function panics if the clock is already mocked on the current thread.
 pub fn mock() -> ClockGuard {
+    mock_with(Config::default())
+}
+
+/// Mocks the clock on the current thread with the given configuration. This
+/// function must **not** be called again before the returned guard is
+/// dropped.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock::{self, Config};
+/// # use std::time::Duration;
+/// let _clock = clock::mock_with(Config {
+///     system_time_start: Duration::from_secs(1_700_000_000),
+///     ..Default::default()
+/// });
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is already mocked on the current thread.
+pub fn mock_with(config: Config) -> ClockGuard {
+    try_mock_with(config).expect("mock called on an already mocked clock")
+}
+
+/// Similar to [`mock`](fn.mock.html), but returns an error instead of
+/// panicking if the clock is already mocked on the current thread.
+pub fn try_mock() -> Result<ClockGuard, ClockError> {
+    try_mock_with(Config::default())
+}
+
+/// Similar to [`mock_with`](fn.mock_with.html), but returns an error instead
+/// of panicking if the clock is already mocked on the current thread.
+pub fn try_mock_with(config: Config) -> Result<ClockGuard, ClockError> {
     STATE.with(|state| {
         let mut state = state.borrow_mut();
         if state.is_some() {
-            panic! {"mock called on an already mocked clock"};
+            Err(ClockError::AlreadyMocked)
         } else {
-            let init = LocalClock::default();
-            init.shared_clock.register_thread();
-            *state = Some(init);
-            ClockGuard {}
+            let frozen = config.frozen;
+            let shared_clock = Arc::new(SharedClock::new(config));
+            let registration = Some(Arc::new(shared_clock.register_thread()));
+            *state = Some(LocalClock {
+                frozen,
+                time: Timepoint::START,
+                shared_clock,
+                registration,
+            });
+            Ok(ClockGuard {})
         }
     })
 }
 
+/// A named, independent mocked clock. Threads that [enter](#method.enter)
+/// different domains do not share a clock, which allows a single test to
+/// simulate e.g. a client and a server whose clocks advance independently.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock::{self, Domain};
+/// # use std::time::Duration;
+/// let client = Domain::new("client");
+/// let server = Domain::new("server");
+/// clock::advance_domains(&[&client, &server], Duration::from_secs(1));
+/// server.advance(Duration::from_secs(1));
+/// {
+///     let _domain = server.enter();
+///     assert_eq!(clock::get(), clock::Timepoint::START + Duration::from_secs(2));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Domain {
+    name: Arc<str>,
+    frozen: bool,
+    shared_clock: Arc<SharedClock>,
+}
+
+impl Domain {
+    /// Creates a new domain with the default configuration.
+    pub fn new(name: &str) -> Self {
+        Self::with_config(name, Config::default())
+    }
+
+    /// Creates a new domain with the given configuration. The clock of a
+    /// thread is frozen when it [enters](#method.enter) a domain configured as
+    /// frozen. As a domain may be [advanced](#method.advance) by any thread,
+    /// deadlocks of frozen threads are not detected.
+    pub fn with_config(name: &str, config: Config) -> Self {
+        Self {
+            name: name.into(),
+            frozen: config.frozen,
+            shared_clock: Arc::new(SharedClock::new(config).without_deadlock_detection()),
+        }
+    }
+
+    /// Returns the name of the domain.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the current shared time of the domain.
+    pub fn time(&self) -> Timepoint {
+        self.shared_clock.time()
+    }
+
+    /// Advances the shared clock of the domain by the given duration, waking
+    /// up all threads of the domain whose timed waits complete. Other domains
+    /// are unaffected.
+    pub fn advance(&self, dur: Duration) {
+        self.shared_clock.advance_by(dur)
+    }
+
+    /// Registers the current thread to the domain until the returned guard is
+    /// dropped. The local clock of the thread starts at the current shared
+    /// time of the domain. If the clock was already mocked on the current
+    /// thread, the thread is unregistered from the previous clock until the
+    /// guard is dropped.
+    pub fn enter(&self) -> DomainGuard {
+        let mut previous = STATE.with(|state| state.borrow_mut().take());
+        if let Some(previous) = previous.as_mut() {
+            previous.registration = None;
+        }
+        register_thread(self.registration_handle());
+        set_frozen(self.frozen);
+        DomainGuard { previous }
+    }
+
+    /// Returns a handle that registers a thread to the domain. Used by e.g.
+    /// `thread::spawn_in`.
+    pub fn registration_handle(&self) -> RegistrationHandle {
+        RegistrationHandle(Some(LocalClock {
+            frozen: false,
+            time: self.shared_clock.time(),
+            shared_clock: self.shared_clock.clone(),
+            registration: None,
+        }))
+    }
+}
+
+impl fmt::Debug for Domain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Domain")
+            .field("name", &self.name)
+            .field("time", &self.time())
+            .finish()
+    }
+}
+
+/// A RAII guard returned by [`Domain::enter`](struct.Domain.html#method.enter).
+/// When this structure is dropped, the current thread leaves the domain.
+#[must_use = "if unused the thread will immediately leave the domain"]
+pub struct DomainGuard {
+    previous: Option<LocalClock>,
+}
+
+impl Drop for DomainGuard {
+    fn drop(&mut self) {
+        let local_clock = STATE.with(|state| state.borrow_mut().take());
+        drop(local_clock);
+        let mut previous = self.previous.take();
+        if let Some(previous) = previous.as_mut() {
+            previous.registration = Some(Arc::new(previous.shared_clock.register_thread()));
+        }
+        STATE.with(|state| *state.borrow_mut() = previous);
+    }
+}
+
+/// Advances the shared clocks of all given domains by the same duration.
+pub fn advance_domains(domains: &[&Domain], dur: Duration) {
+    for domain in domains {
+        domain.advance(dur);
+    }
+}
+
 /// Similar to [`mock`](fn.mock.html) but also freezes the clock on the
 /// current thread.
 /// This causes all mocked routines on the current thread that perform
 /// timed waiting to not increase the local clock automatically. Instead they
 /// wait for the global clock to be manually advanced from another thread.
 ///
+/// If all threads registered to the clock are blocked while at least one of
+/// them waits for the clock to advance, nobody can advance it anymore. Every
+/// waiting thread then panics with a report of all registered threads instead
+/// of hanging forever.
+///
 /// # Panics
 ///
 /// This function panics if the clock is already mocked on the current thread.
 pub fn frozen() -> ClockGuard {
-    let result = mock();
-    set_frozen(true);
-    result
+    mock_with(Config {
+        frozen: true,
+        ..Default::default()
+    })
+}
+
+/// Similar to [`mock`](fn.mock.html) but the shared clock acts as a
+/// discrete-event scheduler.
+/// All mocked routines that perform timed waiting block until every thread
+/// registered to the shared clock is blocked. The shared clock is then
+/// advanced to the earliest time any of those threads waits for and exactly
+/// the threads waiting for this time are woken up.
+///
+/// Threads are only considered blocked while they perform a timed wait or
+/// wait in a mocked blocking routine, e.g. `JoinHandle::join` or
+/// `Condvar::wait`. A registered thread that blocks in any other way
+/// prevents the shared clock from advancing.
+///
+/// # Panics
+///
+/// This function panics if the clock is already mocked on the current thread.
+pub fn scheduled() -> ClockGuard {
+    mock_with(Config {
+        scheduled: true,
+        ..Default::default()
+    })
 }
 
 /// Returns wether the clock is frozen on the current thread.
@@ -163,15 +464,38 @@ fn is_frozen() -> bool {
     })
 }
 
+/// Returns wether the clock is scheduled on the current thread.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+fn is_scheduled() -> bool {
+    STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .expect("chronobreak::clock::is_scheduled requires the clock to be mocked")
+            .shared_clock
+            .is_scheduled()
+    })
+}
+
 /// Unfreezes the clock on the current thread until the returned guard is dropped.
 ///
 /// # Panics
 ///
 /// This function panics if the clock is not mocked on the current thread.
 pub fn unfreeze_scoped() -> UnfreezeGuard {
-    UnfreezeGuard {
-        was_frozen: is_frozen(),
-    }
+    try_unfreeze_scoped()
+        .expect("chronobreak::clock::unfreeze_scoped requires the clock to be mocked")
+}
+
+/// Similar to [`unfreeze_scoped`](fn.unfreeze_scoped.html), but returns an
+/// error instead of panicking if the clock is not mocked.
+pub fn try_unfreeze_scoped() -> Result<UnfreezeGuard, ClockError> {
+    Ok(UnfreezeGuard {
+        was_frozen: try_with_local_clock(|state| state.frozen)?,
+    })
 }
 
 /// Sets the frozen flag for the current thread.
@@ -179,137 +503,1435 @@ pub fn unfreeze_scoped() -> UnfreezeGuard {
 /// # Panics
 ///
 /// This function panics if the clock is not mocked on the current thread.
-fn set_frozen(frozen: bool) {
-    STATE.with(|state| {
+pub(crate) fn set_frozen(frozen: bool) {
+    let was_frozen = STATE.with(|state| {
         state
-            .borrow_mut()
-            .as_mut()
+            .borrow()
+            .as_ref()
             .expect("chronobreak::clock::set_frozen requires the clock to be mocked")
-            .frozen = frozen
+            .frozen
+    });
+    if was_frozen == frozen {
+        return;
+    }
+    let started = Instant::now();
+    await_turn();
+    let local_time = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = state.as_mut().unwrap();
+        state.frozen = frozen;
+        state.time
+    });
+    let kind = if frozen {
+        TraceEventKind::Freeze
+    } else {
+        TraceEventKind::Unfreeze
+    };
+    record(kind, local_time, started);
+}
+
+/// Records a wait requested at the given location with all
+/// [spies](fn.spy.html). Does nothing if the clock is not mocked.
+fn spy_wait(kind: WaitKind, duration: Duration, location: &'static Location<'static>) {
+    STATE.with(|state| {
+        if let Some(state) = state.borrow().as_ref() {
+            state.shared_clock.spy_wait(kind, duration, location);
+        }
     })
 }
 
-/// Sets the local and shared clock to the given timestamp if it is greater
-/// than the current local or global time, respectively.
+/// Records a timed wait for the given duration, which the caller requested,
+/// with all [spies](fn.spy.html). Mocks of timed waits other than sleeps and
+/// delays, e.g. of `Condvar::wait_for` or `thread::park_timeout`, call
+/// this. Does nothing if the clock is not mocked.
+#[track_caller]
+pub fn spy_timed_wait(duration: Duration) {
+    spy_wait(WaitKind::TimedWait, duration, Location::caller())
+}
+
+/// Returns a [`Spy`](struct.Spy.html) that records every wait requested by
+/// any thread registered to the shared clock until it is dropped, i.e. every
+/// sleep, [`DelayFuture`](struct.DelayFuture.html) and
+/// [timed wait](fn.spy_timed_wait.html), with its requested duration, its
+/// caller and its thread. This allows to verify *what* the code under test
+/// waited for, e.g. an exponential backoff, instead of just the resulting
+/// time.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// let spy = clock::spy();
+/// for attempt in 0..4 {
+///     clock::sleep(Duration::from_secs(1 << attempt));
+/// }
+/// spy.assert_sleeps(&[
+///     Duration::from_secs(1),
+///     Duration::from_secs(2),
+///     Duration::from_secs(4),
+///     Duration::from_secs(8),
+/// ]);
+/// ```
 ///
 /// # Panics
 ///
-/// This function panics if the clock is not mocked on the current thread and
-/// if the given time is not a mocked Instant. (Any Instant created without the
-/// clock being mocked.)
-fn advance_to(time: Timepoint) {
+/// This function panics if the clock is not mocked on the current thread.
+pub fn spy() -> Spy {
+    try_with_local_clock(|state| state.shared_clock.spy())
+        .expect("chronobreak::clock::spy requires the clock to be mocked")
+}
+
+/// Updates the [statistics](fn.stats.html) of the current thread with the
+/// given function. Does nothing if the clock is not mocked.
+fn count<F>(f: F)
+where
+    F: FnOnce(&mut Stats),
+{
     STATE.with(|state| {
-        let mut state = state.borrow_mut();
-        let mut state = state
-            .as_mut()
-            .expect("chronobreak::clock::advance_to requires the clock to be mocked");
-        if state.time < time {
-            state.time = time;
-        }
-        if state.frozen {
-            state.shared_clock.advance_to(time);
-        } else {
-            state.shared_clock.unfreeze_advance_to(time);
+        if let Some(state) = state.borrow().as_ref() {
+            state.shared_clock.count(f);
         }
-    });
+    })
 }
 
-/// Advances the local clock by the given duration. Sets the shared clock if
-/// the new local time is greater.
+/// Counts a wait on a mocked `Condvar` in the [statistics](fn.stats.html) of
+/// the current thread. Mocks of condition variables call this. Does nothing
+/// if the clock is not mocked.
+pub fn count_condvar_wait() {
+    count(|stats| stats.condvar_waits += 1)
+}
+
+/// Returns the statistics of the timing operations of all threads registered
+/// to the shared clock, e.g. to assert that a poller does not wake up too
+/// often. The statistics can also be printed when a test fails, see
+/// [`Config::print_stats_on_failure`](struct.Config.html#structfield.print_stats_on_failure).
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::thread;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// for _ in 0..3 {
+///     clock::sleep(Duration::from_secs(20));
+/// }
+/// clock::advance(Duration::from_secs(5));
+/// let stats = clock::stats().thread(thread::current().id());
+/// assert_eq!(stats.sleeps, 3);
+/// assert_eq!(stats.slept, Duration::from_secs(60));
+/// assert_eq!(stats.advances, 1);
+/// assert_eq!(stats.advanced, Duration::from_secs(5));
+/// ```
 ///
 /// # Panics
 ///
 /// This function panics if the clock is not mocked on the current thread.
-pub fn advance(dur: Duration) {
-    if is_mocked() {
-        advance_to(get() + dur);
-    } else {
-        panic! {"chronobreak::clock::advance requires the clock to be mocked"};
+pub fn stats() -> ClockStats {
+    try_with_local_clock(|state| state.shared_clock.stats())
+        .expect("chronobreak::clock::stats requires the clock to be mocked")
+}
+
+/// Blocks the current thread until its next event is due while a trace is
+/// [replayed](fn.replay.html), such that the side effects of the events of
+/// all threads occur in the recorded order. Must be called before the side
+/// effect of an event, which is [recorded](fn.record.html) afterwards, and
+/// without borrowing the local clock. Does nothing if the clock is not mocked.
+///
+/// # Panics
+///
+/// This function panics if the thread does not get its turn within the
+/// [expect timeout](fn.set_expect_timeout.html).
+fn await_turn() {
+    // The shared clock is accessed without borrowing the local clock, as
+    // scheduled callbacks may run while waiting for the turn.
+    let shared_clock = STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .map(|state| state.shared_clock.clone())
+    });
+    if let Some(shared_clock) = shared_clock {
+        if let Err(divergence) = shared_clock.await_replay_turn() {
+            if !thread::panicking() {
+                panic! {"chronobreak replay diverged: {}", divergence};
+            }
+        }
     }
 }
 
-/// Returns the current local time.
+/// Records a clock event of the current thread at the given local time, which
+/// started at the real time `started`, if tracing is enabled. While a trace is
+/// [replayed](fn.replay.html), checks the event against the trace, waiting
+/// until it is due unless the thread [awaited its turn](fn.await_turn.html).
 ///
 /// # Panics
 ///
-/// This function panics if the clock is not mocked on the current thread.
-pub fn get() -> Timepoint {
-    STATE.with(|state| {
+/// This function panics if the event diverges from the replayed trace.
+fn record(kind: TraceEventKind, local_time: Timepoint, started: Instant) {
+    // The shared clock is accessed without borrowing the local clock, as
+    // scheduled callbacks may run while waiting for the event to be due.
+    let shared_clock = STATE.with(|state| {
         state
             .borrow()
             .as_ref()
-            .expect("chronobreak::clock::get requires the clock to be mocked")
-            .time
-    })
+            .map(|state| state.shared_clock.clone())
+    });
+    if let Some(shared_clock) = shared_clock {
+        if let Err(divergence) = shared_clock.record(kind, local_time, started) {
+            if !thread::panicking() {
+                panic! {"chronobreak replay diverged: {}", divergence};
+            }
+        }
+    }
 }
 
-/// A handle that can be used to register a new thread to the same shared clock
-/// that the thread which created this handle is registered to.
-#[derive(Clone)]
-pub struct RegistrationHandle(Option<LocalClock>);
+/// Sets the local and shared clock to the given timestamp if it is greater
+/// than the current local or global time, respectively.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread, if
+/// the current thread is not registered to the shared clock and if the given
+/// time is not a mocked Instant. (Any Instant created without the clock being
+/// mocked.)
+fn advance_to(time: Timepoint) {
+    match try_advance_to(time) {
+        Ok(()) => {}
+        Err(ClockError::NotMocked) => {
+            panic! {"chronobreak::clock::advance_to requires the clock to be mocked"}
+        }
+        Err(error) => panic! {"chronobreak::clock::advance_to failed: {}", error},
+    }
+}
 
-/// Returns a handle to the mocked clock of the current thread. An empty handle
-/// is returned if the clock is not mocked.
-pub fn registration_handle() -> RegistrationHandle {
-    let mut handle = RegistrationHandle(STATE.with(|state| state.borrow().clone()));
-    if let Some(local_state) = handle.0.as_mut() {
-        local_state.frozen = false;
+/// Similar to [`advance_to`](fn.advance_to.html), but returns an error
+/// instead of panicking if the clock is not mocked or the current thread is
+/// not registered to the shared clock.
+fn try_advance_to(time: Timepoint) -> Result<(), ClockError> {
+    // The shared clock is advanced without borrowing the local clock, as
+    // scheduled callbacks may access it.
+    let (shared_clock, frozen, local_time) = try_with_local_clock_mut(|state| {
+        let local_time = state.time;
+        if state.time < time {
+            state.time = time;
+        }
+        (state.shared_clock.clone(), state.frozen, local_time)
+    })?;
+    if shared_clock.is_scheduled() {
+        shared_clock.scheduled_advance_to(local_time, time)
+    } else if frozen {
+        shared_clock.advance_to(local_time, time)
+    } else {
+        shared_clock.unfreeze_advance_to(time);
+        Ok(())
     }
-    handle
 }
 
-/// Registers the given handle for the current thread. The local clock will
-/// have the same time as the local clock of the thread on which
-/// [`registration_handle`](fn.registration_handle.html) has been called but will not be frozen,
-/// independently of whether the original thread had a frozen clock or not.
-/// After the call, both threads will share a common shared clock.
-pub fn register_thread(handle: RegistrationHandle) {
-    if let Some(local_state) = handle.0.as_ref() {
-        local_state.shared_clock.register_thread();
+/// Checks an advance to the given time, which has been requested at the given
+/// location, against the [time limit](fn.set_time_limit.html) of the shared
+/// clock. Sleeps and timed waits, i.e. if `timed_wait` is true, are also
+/// counted and checked against the
+/// [timed wait limit](fn.set_timed_wait_limit.html).
+///
+/// # Panics
+///
+/// This function panics if any limit is exceeded.
+fn check_limits(time: Timepoint, location: &Location, timed_wait: bool) {
+    let result = STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .map(|state| state.shared_clock.check_limits(time, timed_wait))
+    });
+    if let Some(Err(violation)) = result {
+        panic! {"chronobreak clock limit exceeded at {}: {}", location, violation};
     }
-    STATE.with(|state| *state.borrow_mut() = handle.0);
 }
 
-/// A handle that can be used to synchronize a thread's local clock to the time
-/// at which this handle was created.
-#[derive(Clone, Copy)]
-pub struct SyncHandle(Option<Timepoint>);
+/// Advances the local clock by the given duration. Sets the shared clock if
+/// the new local time is greater.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the [time limit](fn.set_time_limit.html) is exceeded.
+#[track_caller]
+pub fn advance(dur: Duration) {
+    match try_advance(dur) {
+        Ok(()) => {}
+        Err(ClockError::NotMocked) => {
+            panic! {"chronobreak::clock::advance requires the clock to be mocked"}
+        }
+        Err(error) => panic! {"chronobreak::clock::advance failed: {}", error},
+    }
+}
 
-/// Returns a synchronization handle with the calling thread's current local
-/// time. An empty handle is returned if the clock is not mocked.
-pub fn sync_handle() -> SyncHandle {
-    if is_mocked() {
-        SyncHandle(Some(get()))
-    } else {
-        SyncHandle(None)
+/// Similar to [`advance`](fn.advance.html), but returns an error instead of
+/// panicking if the clock is not mocked.
+///
+/// # Panics
+///
+/// This function panics if the [time limit](fn.set_time_limit.html) is
+/// exceeded.
+#[track_caller]
+pub fn try_advance(dur: Duration) -> Result<(), ClockError> {
+    try_advance_with_jitter(dur, Duration::default(), AdvanceKind::Advance)
+}
+
+/// Advances the local clock by the given duration plus the given jitter.
+#[track_caller]
+fn try_advance_with_jitter(
+    dur: Duration,
+    jitter: Duration,
+    kind: AdvanceKind,
+) -> Result<(), ClockError> {
+    let time = try_get()? + dur + jitter;
+    if kind == AdvanceKind::Sleep {
+        spy_wait(WaitKind::Sleep, dur, Location::caller());
     }
+    advance_local_to(time, Location::caller(), kind)
 }
 
-/// Synchronizes the calling thread's local clock with the synchronization
-/// handle.
-pub fn sync_with(handle: SyncHandle) {
-    if let Some(timepoint) = handle.0 {
-        advance_to(timepoint);
-    } else if is_mocked() {
-        panic! {"chronobreak::clock::sync_with called with an unmocked SyncHandle on a mocked clock"}
+/// The kind of an advance of the local clock by
+/// [`advance_local_to`](fn.advance_local_to.html).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum AdvanceKind {
+    /// A mocked sleep, which advances the shared clock like
+    /// [`advance_to`](fn.advance_to.html).
+    Sleep,
+    /// An explicit advance, e.g. by a test driving the clock, which advances
+    /// the shared clock like [`advance_to`](fn.advance_to.html).
+    Advance,
+    /// An explicit advance, which advances the shared clock immediately, even
+    /// if the clock is frozen or scheduled.
+    Immediate,
+}
+
+/// Advances the local clock to the given time, which has been requested at
+/// the given location. The advance is checked against the limits, counted in
+/// the statistics as a sleep or an advance and recorded in the trace.
+fn advance_local_to(
+    time: Timepoint,
+    location: &Location,
+    kind: AdvanceKind,
+) -> Result<(), ClockError> {
+    let started = Instant::now();
+    let local_time = try_get()?;
+    check_limits(time, location, kind == AdvanceKind::Sleep);
+    let dur = time.saturating_duration_since(local_time);
+    count(|stats| {
+        if kind == AdvanceKind::Sleep {
+            stats.sleeps += 1;
+            stats.slept += dur;
+        } else {
+            stats.advances += 1;
+            stats.advanced += dur;
+        }
+    });
+    await_turn();
+    if kind != AdvanceKind::Immediate {
+        try_advance_to(time)?;
+    } else {
+        let shared_clock = try_with_local_clock_mut(|state| {
+            state.time = state.time.max(time);
+            state.shared_clock.clone()
+        })?;
+        shared_clock.unfreeze_advance_to(time);
     }
+    let event = match kind {
+        AdvanceKind::Sleep => TraceEventKind::Sleep { to: time },
+        AdvanceKind::Advance | AdvanceKind::Immediate => TraceEventKind::Advance { to: time },
+    };
+    record(event, local_time, started);
+    Ok(())
 }
 
-/// Blocks the current thread until the thread with the given thread id enters
-/// a timed wait.
+/// Sleeps for the given duration plus the [wait jitter](fn.set_wait_jitter.html),
+/// i.e. advances the local clock like [`advance`](fn.advance.html). Mocks of
+/// sleeps, e.g. of `thread::sleep`, call this.
 ///
 /// # Panics
 ///
-/// This function panics if the clock is not mocked on the current thread.
-pub fn expect_timed_wait_on(id: ThreadId) {
+/// This function panics if the clock is not mocked on the current thread or
+/// if the [time limit](fn.set_time_limit.html) or the
+/// [timed wait limit](fn.set_timed_wait_limit.html) is exceeded.
+#[track_caller]
+pub fn sleep(dur: Duration) {
+    match try_advance_with_jitter(dur, wait_jitter(), AdvanceKind::Sleep) {
+        Ok(()) => {}
+        Err(ClockError::NotMocked) => {
+            panic! {"chronobreak::clock::sleep requires the clock to be mocked"}
+        }
+        Err(error) => panic! {"chronobreak::clock::sleep failed: {}", error},
+    }
+}
+
+/// Returns the overshoot of the next timed wait of the current thread, which
+/// is zero if the clock is not mocked or the jitter is disabled.
+fn wait_jitter() -> Duration {
     STATE.with(|state| {
         state
             .borrow()
             .as_ref()
-            .expect(
-                "chronobreak::clock::expect_blocking_advance_on requires the clock to be mocked",
-            )
+            .map(|state| state.shared_clock.wait_jitter())
+            .unwrap_or_default()
+    })
+}
+
+/// Lets every mocked sleep and [`DelayFuture`](struct.DelayFuture.html)
+/// overshoot its requested duration by a random duration drawn from the
+/// given distribution, like real sleeps do. This allows to test code that
+/// wrongly assumes sleeps to be exact. The overshoots are reproducible, as
+/// they only depend on the seed and the order of the waits of each thread.
+///
+/// The seed can be overridden through the environment variable
+/// `CHRONOBREAK_JITTER_SEED`, e.g. to reproduce a failure. If the thread that
+/// mocked the clock panics, the seed is printed to stderr.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock::{self, Distribution};
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// clock::set_wait_jitter(
+///     Distribution::Uniform {
+///         min: Duration::from_millis(0),
+///         max: Duration::from_millis(10),
+///     },
+///     42,
+/// );
+/// let start = clock::get();
+/// clock::sleep(Duration::from_secs(1));
+/// assert!(clock::get() >= start + Duration::from_secs(1));
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if `CHRONOBREAK_JITTER_SEED` is set but is not a valid seed.
+pub fn set_wait_jitter(distribution: Distribution, seed: u64) {
+    let seed = match env::var(JITTER_SEED_VAR) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            panic! {"{} must be an unsigned integer, but is `{}`", JITTER_SEED_VAR, value}
+        }),
+        Err(_) => seed,
+    };
+    try_with_local_clock(|state| {
+        state
             .shared_clock
-            .expect_timed_wait_on(id)
-    });
+            .set_wait_jitter(Some((distribution, seed)))
+    })
+    .expect("chronobreak::clock::set_wait_jitter requires the clock to be mocked")
+}
+
+/// Disables the [wait jitter](fn.set_wait_jitter.html).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn clear_wait_jitter() {
+    try_with_local_clock(|state| state.shared_clock.set_wait_jitter(None))
+        .expect("chronobreak::clock::clear_wait_jitter requires the clock to be mocked")
+}
+
+/// Returns the seed of the [wait jitter](fn.set_wait_jitter.html), or `None`
+/// if it is disabled.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn wait_jitter_seed() -> Option<u64> {
+    try_with_local_clock(|state| state.shared_clock.wait_jitter_seed())
+        .expect("chronobreak::clock::wait_jitter_seed requires the clock to be mocked")
+}
+
+/// Sets the maximum time the shared clock may be advanced to, relative to
+/// [`Timepoint::START`](struct.Timepoint.html#associatedconstant.START).
+/// Any sleep, timed wait or explicit advance beyond this time panics with its
+/// call site and the state of all registered threads, which turns endless
+/// sleep loops into fast failures. See also [`Config::time_limit`](struct.Config.html#structfield.time_limit).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_time_limit(limit: Duration) {
+    try_with_local_clock(|state| state.shared_clock.set_time_limit(Some(limit)))
+        .expect("chronobreak::clock::set_time_limit requires the clock to be mocked")
+}
+
+/// Sets the maximum number of sleeps and timed waits all threads registered
+/// to the shared clock may perform in total. Exceeding it panics like
+/// exceeding the [time limit](fn.set_time_limit.html). Explicit advances of
+/// the clock, e.g. by [`advance`](fn.advance.html) or
+/// [`run_until_idle`](fn.run_until_idle.html), do not count.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_timed_wait_limit(limit: u64) {
+    try_with_local_clock(|state| state.shared_clock.set_timed_wait_limit(Some(limit)))
+        .expect("chronobreak::clock::set_timed_wait_limit requires the clock to be mocked")
+}
+
+/// Returns the current local time.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn get() -> Timepoint {
+    try_get().expect("chronobreak::clock::get requires the clock to be mocked")
+}
+
+/// Similar to [`get`](fn.get.html), but returns an error instead of
+/// panicking if the clock is not mocked.
+pub fn try_get() -> Result<Timepoint, ClockError> {
+    try_with_local_clock(|state| state.time)
+}
+
+/// Returns the current local time as observed by the current thread, i.e. with
+/// the thread's [skew](fn.set_skew.html) and [drift](fn.set_drift.html)
+/// applied and offset by [`Config::instant_start`](struct.Config.html#structfield.instant_start).
+/// This is the time returned by a mocked `Instant::now()`, while sleeps and
+/// timed waits are based on [`get`](fn.get.html). Reading the time may advance
+/// the clock, if [auto-ticking](fn.set_auto_tick.html) is enabled.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn now() -> Timepoint {
+    try_now().expect("chronobreak::clock::now requires the clock to be mocked")
+}
+
+/// Similar to [`now`](fn.now.html), but returns an error instead of
+/// panicking if the clock is not mocked.
+pub fn try_now() -> Result<Timepoint, ClockError> {
+    let now = try_with_local_clock(|state| {
+        let instant_start = state.shared_clock.instant_start();
+        state
+            .shared_clock
+            .observed_time(state.time, |time| time + instant_start)
+    })?;
+    count(|stats| stats.now_reads += 1);
+    auto_tick();
+    Ok(now)
+}
+
+/// Advances the local clock of the current thread if it read the current time
+/// often enough without progress. The shared clock is advanced without
+/// blocking, even if the clock is frozen or scheduled.
+fn auto_tick() {
+    let ticked = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        let state = state.as_mut()?;
+        let tick = state.shared_clock.auto_tick(state.time)?;
+        state.time += tick;
+        Some((state.shared_clock.clone(), state.time))
+    });
+    if let Some((shared_clock, time)) = ticked {
+        shared_clock.unfreeze_advance_to(time);
+    }
+}
+
+/// Advances the clock by `tick` on every read of the current time through
+/// [`now`](fn.now.html) or [`system_time`](fn.system_time.html), e.g. by a
+/// mocked `Instant::now()`. This allows to test code that busy-waits for a
+/// deadline, which would otherwise spin forever as the mocked clock only
+/// advances on sleeps and timed waits. A zero `tick` disables auto-ticking.
+///
+/// The read that triggers a tick still returns the time before the tick.
+/// Ticks advance the shared clock without blocking, even if the clock is
+/// [frozen](fn.frozen.html) or [scheduled](fn.scheduled.html).
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// clock::set_auto_tick(Duration::from_millis(1));
+/// let deadline = clock::now() + Duration::from_millis(10);
+/// while clock::now() < deadline {}
+/// assert_eq!(clock::now(), deadline + Duration::from_millis(1));
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_auto_tick(tick: Duration) {
+    set_auto_tick_after(1, tick)
+}
+
+/// Similar to [`set_auto_tick`](fn.set_auto_tick.html), but the clock of a
+/// thread only advances after it read the current time `reads` times in a
+/// row without the time progressing.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_auto_tick_after(reads: usize, tick: Duration) {
+    try_with_local_clock(|state| state.shared_clock.set_auto_tick(reads, tick))
+        .expect("chronobreak::clock::set_auto_tick requires the clock to be mocked")
+}
+
+/// Sets the constant offset of the given thread's clock from the shared
+/// clock. Only the time the thread observes, i.e. [`now`](fn.now.html) and
+/// [`system_time`](fn.system_time.html), is affected, so the thread still
+/// synchronizes with other threads as usual.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_skew(id: ThreadId, skew: Skew) {
+    try_with_local_clock(|state| state.shared_clock.set_skew(id, skew))
+        .expect("chronobreak::clock::set_skew requires the clock to be mocked")
+}
+
+/// Sets the drift of the given thread's clock, i.e. from now on it runs faster
+/// or slower than the shared clock by the given parts per million. A value of
+/// 0 restores the normal rate. Just like the [skew](fn.set_skew.html), the
+/// drift only affects the time the thread observes.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if `ppm` is not greater than -1,000,000.
+pub fn set_drift(id: ThreadId, ppm: i64) {
+    try_with_local_clock(|state| state.shared_clock.set_drift(id, ppm))
+        .expect("chronobreak::clock::set_drift requires the clock to be mocked")
+}
+
+/// Returns the current local system time. It starts at
+/// [`Config::system_time_start`](struct.Config.html#structfield.system_time_start)
+/// after the unix epoch and advances with the local time, unless it is
+/// changed by e.g. [`step_system_time_backward`](fn.step_system_time_backward.html).
+/// Like [`now`](fn.now.html), the system time includes the current thread's
+/// skew and drift and reading it may [auto-tick](fn.set_auto_tick.html) the
+/// clock.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn system_time() -> Timepoint {
+    let system_time = try_with_local_clock(|state| {
+        let shared_clock = &state.shared_clock;
+        shared_clock.observed_time(state.time, |time| shared_clock.system_time(time))
+    })
+    .expect("chronobreak::clock::system_time requires the clock to be mocked");
+    auto_tick();
+    system_time
+}
+
+fn unskewed_system_time() -> Timepoint {
+    try_with_local_clock(|state| state.shared_clock.system_time(state.time))
+        .expect("chronobreak::clock::system_time requires the clock to be mocked")
+}
+
+/// Sets the system time, such that [`system_time`](fn.system_time.html)
+/// currently returns the given duration after the unix epoch. The system
+/// time then advances from this time on, while the local time is unaffected.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_system_time(since_epoch: Duration) {
+    try_with_local_clock(|state| {
+        state
+            .shared_clock
+            .set_system_time(state.time, Timepoint::START + since_epoch)
+    })
+    .expect("chronobreak::clock::set_system_time requires the clock to be mocked")
+}
+
+/// Steps the system time forward by the given duration, like a wall clock
+/// that is corrected by e.g. NTP. The local time is unaffected.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn step_system_time_forward(dur: Duration) {
+    set_system_time(unskewed_system_time().duration_since(Timepoint::START) + dur);
+}
+
+/// Steps the system time backward by the given duration, like a wall clock
+/// that is corrected by e.g. NTP. The local time is unaffected.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the system time would be stepped before the unix epoch.
+pub fn step_system_time_backward(dur: Duration) {
+    let since_epoch = unskewed_system_time()
+        .duration_since(Timepoint::START)
+        .checked_sub(dur)
+        .expect("chronobreak::clock::step_system_time_backward may not step before the unix epoch");
+    set_system_time(since_epoch);
+}
+
+/// Slews the system time, i.e. from now on it runs faster or slower than the
+/// local time by the given parts per million. A value of 0 restores the
+/// normal rate. The local time is unaffected.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if `ppm` is not greater than -1,000,000.
+pub fn slew_system_time(ppm: i64) {
+    try_with_local_clock(|state| state.shared_clock.slew_system_time(state.time, ppm))
+        .expect("chronobreak::clock::slew_system_time requires the clock to be mocked")
+}
+
+/// Returns the current local boot time, i.e. the mocked equivalent of
+/// `CLOCK_BOOTTIME` on Linux. It advances with the local time and
+/// additionally by any duration the system has been
+/// [suspended](fn.suspend_for.html) for.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn boot_time() -> Timepoint {
+    try_with_local_clock(|state| state.shared_clock.boot_time(state.time))
+        .expect("chronobreak::clock::boot_time requires the clock to be mocked")
+}
+
+/// Simulates suspending the system for the given duration. Like on Linux,
+/// the [system time](fn.system_time.html) and the
+/// [boot time](fn.boot_time.html) advance by the given duration, while the
+/// local and shared time, and therefore any mocked `Instant`, do not.
+/// Consequently, pending timed waits and
+/// [`DelayFuture`](struct.DelayFuture.html)s do not complete due to the
+/// suspend, just like timers based on the monotonic clock after a real
+/// resume.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn suspend_for(dur: Duration) {
+    try_with_local_clock(|state| state.shared_clock.suspend_for(state.time, dur))
+        .expect("chronobreak::clock::suspend_for requires the clock to be mocked")
+}
+
+/// A handle that can be used to register a new thread to the same shared clock
+/// that the thread which created this handle is registered to.
+#[derive(Clone)]
+pub struct RegistrationHandle(Option<LocalClock>);
+
+/// Returns a handle to the mocked clock of the current thread. An empty handle
+/// is returned if the clock is not mocked.
+pub fn registration_handle() -> RegistrationHandle {
+    let mut handle = RegistrationHandle(STATE.with(|state| state.borrow().clone()));
+    if let Some(local_state) = handle.0.as_mut() {
+        local_state.frozen = false;
+        local_state.registration = None;
+    }
+    handle
+}
+
+/// Registers the given handle for the current thread. The local clock will
+/// have the same time as the local clock of the thread on which
+/// [`registration_handle`](fn.registration_handle.html) has been called but will not be frozen,
+/// independently of whether the original thread had a frozen clock or not.
+/// After the call, both threads will share a common shared clock.
+pub fn register_thread(handle: RegistrationHandle) {
+    let previous = STATE.with(|state| state.borrow_mut().take());
+    drop(previous);
+    let mut local_state = handle.0;
+    if let Some(local_state) = local_state.as_mut() {
+        local_state.registration = Some(Arc::new(local_state.shared_clock.register_thread()));
+    }
+    STATE.with(|state| *state.borrow_mut() = local_state);
+}
+
+/// A handle that can be used to synchronize a thread's local clock to the time
+/// at which this handle was created.
+#[derive(Clone, Copy)]
+pub struct SyncHandle(Option<(Timepoint, usize)>);
+
+/// Returns the address of the shared clock of the current thread, which
+/// identifies its [`Domain`](struct.Domain.html).
+fn shared_clock_address() -> Option<usize> {
+    STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .map(|state| Arc::as_ptr(&state.shared_clock) as usize)
+    })
+}
+
+/// Returns a synchronization handle with the calling thread's current local
+/// time. An empty handle is returned if the clock is not mocked.
+pub fn sync_handle() -> SyncHandle {
+    SyncHandle(shared_clock_address().map(|address| (get(), address)))
+}
+
+/// Synchronizes the calling thread's local clock with the synchronization
+/// handle. Handles created in a different [`Domain`](struct.Domain.html)
+/// are ignored, as the clocks of different domains are independent.
+///
+/// # Panics
+///
+/// This function panics if the handle has been created while the clock was
+/// mocked but the clock is not mocked on the current thread, or vice versa.
+pub fn sync_with(handle: SyncHandle) {
+    if let Err(error) = try_sync_with(handle) {
+        panic! {"chronobreak::clock::sync_with failed: {}", error};
+    }
+}
+
+/// Similar to [`sync_with`](fn.sync_with.html), but returns
+/// [`ClockError::MixedMockedAndActual`](enum.ClockError.html#variant.MixedMockedAndActual)
+/// instead of panicking if the handle and the current thread do not agree on
+/// whether the clock is mocked.
+pub fn try_sync_with(handle: SyncHandle) -> Result<(), ClockError> {
+    match (handle.0, shared_clock_address()) {
+        (None, None) => Ok(()),
+        (Some((_, address)), Some(current)) if current != address => Ok(()),
+        (Some((timepoint, _)), Some(_)) => {
+            let started = Instant::now();
+            let local_time = try_get()?;
+            if local_time < timepoint {
+                await_turn();
+            }
+            try_advance_to(timepoint)?;
+            if local_time < timepoint {
+                count(|stats| stats.sync_advances += 1);
+                record(TraceEventKind::Sync { to: timepoint }, local_time, started);
+            }
+            Ok(())
+        }
+        _ => Err(ClockError::MixedMockedAndActual),
+    }
+}
+
+/// Blocks the current thread until the thread with the given thread id enters
+/// a timed wait.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the thread does not enter a timed wait within the
+/// [expect timeout](fn.set_expect_timeout.html).
+#[track_caller]
+pub fn expect_timed_wait_on(id: ThreadId) {
+    let timeout = try_with_local_clock(|state| state.shared_clock.expect_timeout())
+        .expect("chronobreak::clock::expect_timed_wait_on requires the clock to be mocked");
+    if let Err(error) = try_expect_timed_wait_on(id, timeout) {
+        panic! {"{}", error};
+    }
+}
+
+/// Blocks the current thread until the thread with the given thread id enters
+/// a timed wait or until the given real time has passed. In the latter case,
+/// the returned [`ClockError::ExpectTimeout`](enum.ClockError.html#variant.ExpectTimeout)
+/// describes what the thread was doing instead.
+pub fn try_expect_timed_wait_on(id: ThreadId, real_timeout: Duration) -> Result<(), ClockError> {
+    try_with_local_clock(|state| state.shared_clock.expect_timed_wait_on(id, real_timeout))?
+}
+
+/// Returns the number of timed waits the thread with the given thread id has
+/// started so far. The generation increases by one with every timed wait.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the given thread is not registered to the clock.
+pub fn timed_wait_generation(id: ThreadId) -> u64 {
+    match try_timed_wait_generation(id) {
+        Ok(generation) => generation,
+        Err(error) => panic! {"chronobreak::clock::timed_wait_generation failed: {}", error},
+    }
+}
+
+/// Similar to [`timed_wait_generation`](fn.timed_wait_generation.html), but
+/// returns an error instead of panicking.
+pub fn try_timed_wait_generation(id: ThreadId) -> Result<u64, ClockError> {
+    try_with_local_clock(|state| state.shared_clock.timed_wait_generation(id))?
+}
+
+/// Blocks the current thread until the thread with the given thread id has
+/// started at least `count` timed waits in total, i.e. until its
+/// [timed wait generation](fn.timed_wait_generation.html) reaches `count`.
+/// Returns the most recent timed wait of the thread.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread, if
+/// `count` is zero or if the thread does not start enough timed waits within
+/// the [expect timeout](fn.set_expect_timeout.html).
+#[track_caller]
+pub fn expect_timed_waits_on(id: ThreadId, count: u64) -> TimedWaitInfo {
+    let result = try_with_local_clock(|state| {
+        let shared_clock = &state.shared_clock;
+        shared_clock.expect_timed_waits_on(id, count, shared_clock.expect_timeout())
+    })
+    .expect("chronobreak::clock::expect_timed_waits_on requires the clock to be mocked");
+    match result {
+        Ok(info) => info,
+        Err(error) => panic! {"{}", error},
+    }
+}
+
+/// Blocks the current thread until the thread with the given thread id starts
+/// a new timed wait, even if it is already in a timed wait. Returns the new
+/// timed wait.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the thread does not start a timed wait within the
+/// [expect timeout](fn.set_expect_timeout.html).
+#[track_caller]
+pub fn expect_next_timed_wait_on(id: ThreadId) -> TimedWaitInfo {
+    expect_timed_waits_on(id, timed_wait_generation(id) + 1)
+}
+
+/// Blocks the current thread until at least `count` other threads registered
+/// to the shared clock are in a timed wait.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if not enough threads enter a timed wait within the
+/// [expect timeout](fn.set_expect_timeout.html).
+#[track_caller]
+pub fn expect_timed_waits(count: usize) {
+    expect_timed_waits_impl(None, Some(count))
+}
+
+/// Similar to [`expect_timed_waits`](fn.expect_timed_waits.html), but only
+/// threads whose name starts with the given prefix are counted.
+#[track_caller]
+pub fn expect_timed_waits_with_prefix(prefix: &str, count: usize) {
+    expect_timed_waits_impl(Some(prefix), Some(count))
+}
+
+/// Blocks the current thread until all other threads registered to the
+/// shared clock are in a timed wait.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if not all threads enter a timed wait within the
+/// [expect timeout](fn.set_expect_timeout.html).
+#[track_caller]
+pub fn expect_all_timed_waiting() {
+    expect_timed_waits_impl(None, None)
+}
+
+/// Similar to [`expect_all_timed_waiting`](fn.expect_all_timed_waiting.html),
+/// but only threads whose name starts with the given prefix are considered.
+#[track_caller]
+pub fn expect_all_timed_waiting_with_prefix(prefix: &str) {
+    expect_timed_waits_impl(Some(prefix), None)
+}
+
+#[track_caller]
+fn expect_timed_waits_impl(prefix: Option<&str>, count: Option<usize>) {
+    let result = try_with_local_clock(|state| {
+        let shared_clock = &state.shared_clock;
+        shared_clock.expect_timed_waits(prefix, count, shared_clock.expect_timeout())
+    })
+    .expect("chronobreak::clock::expect_timed_waits requires the clock to be mocked");
+    if let Err(error) = result {
+        panic! {"{}", error};
+    }
+}
+
+/// Sets the real time after which [`expect_timed_wait_on`](fn.expect_timed_wait_on.html)
+/// and similar expectations panic. Defaults to 30 seconds.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn set_expect_timeout(real_timeout: Duration) {
+    try_with_local_clock(|state| state.shared_clock.set_expect_timeout(real_timeout))
+        .expect("chronobreak::clock::set_expect_timeout requires the clock to be mocked")
+}
+
+/// Returns all timers registered to the shared clock, e.g. by pending
+/// [`DelayFuture`](struct.DelayFuture.html)s, ordered by their deadline.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn pending_timers() -> Vec<PendingTimer> {
+    try_with_local_clock(|state| state.shared_clock.pending_timers())
+        .expect("chronobreak::clock::pending_timers requires the clock to be mocked")
+}
+
+/// Returns all threads that are currently blocked in a timed wait on a
+/// frozen or [`scheduled`](fn.scheduled.html) clock, ordered by the time they
+/// wait for.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn timed_waiters() -> Vec<TimedWaiter> {
+    try_with_local_clock(|state| state.shared_clock.timed_waiters())
+        .expect("chronobreak::clock::timed_waiters requires the clock to be mocked")
+}
+
+/// Returns the earliest deadline of all [pending timers](fn.pending_timers.html)
+/// and [timed waiters](fn.timed_waiters.html), if any.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn next_deadline() -> Option<Timepoint> {
+    try_with_local_clock(|state| state.shared_clock.next_deadline())
+        .expect("chronobreak::clock::next_deadline requires the clock to be mocked")
+}
+
+/// Waits until all other threads registered to the shared clock are blocked,
+/// then advances the shared clock to the [next deadline](fn.next_deadline.html),
+/// completing the earliest timed waits and timers. The local clock is advanced
+/// as well, even if it is frozen. Returns the new time or `None` if nothing
+/// is pending.
+///
+/// Only threads that wait in a timed wait or a mocked blocking routine, e.g.
+/// `JoinHandle::join`, are considered blocked. This function therefore hangs
+/// if another registered thread is blocked in some other way.
+///
+/// Like [`advance`](fn.advance.html), the advance is checked against the
+/// [time limit](fn.set_time_limit.html) and recorded in the
+/// [statistics](fn.stats.html) and the [trace](fn.start_trace.html).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the [time limit](fn.set_time_limit.html) or the
+/// [timed wait limit](fn.set_timed_wait_limit.html) is exceeded.
+#[track_caller]
+pub fn advance_to_next_timer() -> Option<Timepoint> {
+    let shared_clock = try_with_local_clock(|state| state.shared_clock.clone())
+        .expect("chronobreak::clock::advance_to_next_timer requires the clock to be mocked");
+    shared_clock.wait_until_idle();
+    let time = shared_clock.next_deadline()?;
+    advance_local_to(time, Location::caller(), AdvanceKind::Immediate).unwrap();
+    Some(time)
+}
+
+/// Repeatedly [advances to the next timer](fn.advance_to_next_timer.html)
+/// until nothing is pending anymore or the next deadline lies more than
+/// `limit` after the current local time. Returns `true` if the clock became
+/// idle and `false` if the limit has been hit.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread or
+/// if the [time limit](fn.set_time_limit.html) or the
+/// [timed wait limit](fn.set_timed_wait_limit.html) is exceeded.
+#[track_caller]
+pub fn run_until_idle(limit: Duration) -> bool {
+    let limit = get() + limit;
+    loop {
+        let shared_clock = try_with_local_clock(|state| state.shared_clock.clone())
+            .expect("chronobreak::clock::run_until_idle requires the clock to be mocked");
+        shared_clock.wait_until_idle();
+        match shared_clock.next_deadline() {
+            None => return true,
+            Some(time) if time > limit => return false,
+            Some(_) => {
+                advance_to_next_timer();
+            }
+        }
+    }
+}
+
+/// A callback registered through [`schedule_at`](fn.schedule_at.html), which
+/// is called when its waker is woken up.
+struct ScheduledCallback(Mutex<Option<Box<dyn FnOnce() + Send>>>);
+
+impl Wake for ScheduledCallback {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        let callback = self.0.lock().unwrap().take();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+}
+
+/// Calls `f` as soon as the shared clock reaches the given time, e.g. to drop
+/// a connection or to push a configuration change at a precise point in
+/// virtual time. `f` is called immediately if the shared clock already reached
+/// the given time.
+///
+/// The callback runs on the thread that advances the shared clock, right after
+/// the clock has been advanced and before that thread resumes. The local time
+/// of this thread may thus already be past the given time. Like timers, a
+/// pending callback is reported by [`pending_timers`](fn.pending_timers.html)
+/// and lets a [`scheduled`](fn.scheduled.html) clock advance to its time. The
+/// callback must not block on other threads registered to the clock, which
+/// may wait for the advancing thread.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use std::sync::Arc;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// let connected = Arc::new(AtomicBool::new(true));
+/// let connection = connected.clone();
+/// clock::schedule_after(Duration::from_secs(10), move || {
+///     connection.store(false, Ordering::SeqCst)
+/// });
+/// clock::advance(Duration::from_secs(9));
+/// assert!(connected.load(Ordering::SeqCst));
+/// clock::advance(Duration::from_secs(1));
+/// assert!(!connected.load(Ordering::SeqCst));
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn schedule_at<F>(time: Timepoint, f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let callback = Arc::new(ScheduledCallback(Mutex::new(Some(Box::new(f)))));
+    let waker = try_with_local_clock(|state| {
+        state
+            .shared_clock
+            .schedule_waker(Waker::from(callback), time)
+    })
+    .expect("chronobreak::clock::schedule_at requires the clock to be mocked");
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Calls `f` as soon as the shared clock reaches the given duration after the
+/// current local time. See [`schedule_at`](fn.schedule_at.html).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn schedule_after<F>(dur: Duration, f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let time =
+        try_get().expect("chronobreak::clock::schedule_after requires the clock to be mocked");
+    schedule_at(time + dur, f)
+}
+
+/// Starts recording all events of the shared clock, i.e. sleeps and other
+/// advances of local clocks, synchronizations through mocked synchronization
+/// primitives, registrations and wake-ups of
+/// [`DelayFuture`](struct.DelayFuture.html)s and freeze transitions. Each
+/// event is recorded with its thread and with its virtual and real
+/// timestamps. Previously recorded events are discarded.
+///
+/// With the `chrome-trace` feature, the recorded [`Trace`](struct.Trace.html)
+/// can be exported as Chrome trace event JSON and opened in e.g. Perfetto to
+/// inspect the virtual timeline of a test.
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// clock::start_trace();
+/// clock::advance(Duration::from_secs(1));
+/// let trace = clock::stop_trace();
+/// assert_eq!(trace.events().len(), 1);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn start_trace() {
+    try_with_local_clock(|state| state.shared_clock.start_trace())
+        .expect("chronobreak::clock::start_trace requires the clock to be mocked")
+}
+
+/// Stops recording events of the shared clock and returns the recorded
+/// trace. The trace is empty if recording has not been
+/// [started](fn.start_trace.html).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn stop_trace() -> Trace {
+    try_with_local_clock(|state| state.shared_clock.stop_trace())
+        .expect("chronobreak::clock::stop_trace requires the clock to be mocked")
+}
+
+/// Returns the events of the shared clock recorded so far without stopping
+/// the recording.
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+pub fn trace() -> Trace {
+    try_with_local_clock(|state| state.shared_clock.trace())
+        .expect("chronobreak::clock::trace requires the clock to be mocked")
+}
+
+/// Calls the given function while replaying a [trace](fn.start_trace.html)
+/// recorded by a previous run, e.g. of a flaky test, and returns its result.
+///
+/// Every sleep, synchronization, timer event and freeze transition waits until
+/// it is due according to the trace before it takes effect, which forces all
+/// threads registered to the shared clock into the recorded order. Threads are identified by the
+/// order in which they registered to the shared clock, i.e. the thread that
+/// mocked the clock is `#0` and spawned threads are numbered in the order in
+/// which they have been spawned. With the `chrome-trace` feature, a trace
+/// exported by a CI run can be loaded with
+/// [`Trace::from_chrome_json`](struct.Trace.html#method.from_chrome_json).
+///
+/// # Examples
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use std::time::Duration;
+/// let _clock = clock::mock();
+/// let test = || {
+///     clock::advance(Duration::from_secs(1));
+///     clock::advance(Duration::from_secs(2));
+/// };
+/// clock::start_trace();
+/// test();
+/// let trace = clock::stop_trace();
+/// # drop(_clock);
+///
+/// let _clock = clock::mock();
+/// clock::replay(&trace, test);
+/// ```
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked on the current thread.
+/// A thread whose event diverges from the trace panics with the first
+/// divergence, while all other threads continue without being ordered
+/// anymore. If the function returns or panics after the run diverged, or
+/// returns before all recorded events have been replayed, this function
+/// panics with the first divergence as well.
+pub fn replay<F, R>(trace: &Trace, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    let shared_clock = try_with_local_clock(|state| state.shared_clock.clone())
+        .expect("chronobreak::clock::replay requires the clock to be mocked");
+    shared_clock.start_replay(trace);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    match (result, shared_clock.finish_replay()) {
+        (Ok(result), Ok(())) => result,
+        (Err(payload), Err(ReplayError::Incomplete { .. })) | (Err(payload), Ok(())) => {
+            panic::resume_unwind(payload)
+        }
+        (_, Err(divergence)) => panic! {"chronobreak replay diverged: {}", divergence},
+    }
+}
+
+/// A RAII guard returned by [`blocking`](fn.blocking.html). When this
+/// structure is dropped, the current thread is considered runnable again.
+/// On a [`scheduled`](fn.scheduled.html) clock, the local clock is then
+/// synchronized to the shared clock, as time may have passed while blocking.
+#[must_use = "if unused the thread will immediately be considered runnable again"]
+pub struct BlockingGuard(Option<shared_clock::BlockingGuard>);
+
+impl Drop for BlockingGuard {
+    fn drop(&mut self) {
+        if self.0.take().is_some() && is_scheduled() {
+            let time = try_with_local_clock(|state| state.shared_clock.time())
+                .expect("chronobreak::BlockingGuard::drop requires the clock to be mocked");
+            advance_to(time);
+        }
+    }
+}
+
+/// Marks the current thread as blocked on the given blocker until the
+/// returned guard is dropped. Mocks of blocking routines use this to let a
+/// [`scheduled`](fn.scheduled.html) clock know when all threads are blocked.
+/// An empty guard is returned if the clock is not mocked.
+pub fn blocking(blocker: Blocker) -> BlockingGuard {
+    BlockingGuard(STATE.with(|state| {
+        state
+            .borrow()
+            .as_ref()
+            .map(|state| state.shared_clock.notify_blocking(blocker))
+    }))
+}
+
+/// Marks at most one thread that is blocked on the given blocker as runnable.
+/// Does nothing if the clock is not mocked.
+pub fn unblock_one(blocker: Blocker) {
+    STATE.with(|state| {
+        if let Some(state) = state.borrow().as_ref() {
+            state.shared_clock.unblock_one(blocker)
+        }
+    })
+}
+
+/// Marks all threads that are blocked on the given blocker as runnable.
+/// Does nothing if the clock is not mocked.
+pub fn unblock_all(blocker: Blocker) {
+    STATE.with(|state| {
+        if let Some(state) = state.borrow().as_ref() {
+            state.shared_clock.unblock_all(blocker)
+        }
+    })
+}
+
+/// Waker of [`block_on`](fn.block_on.html) that unparks the blocked thread.
+struct ThreadWaker {
+    thread: thread::Thread,
+    woken: AtomicBool,
+    shared_clock: Option<Arc<SharedClock>>,
+}
+
+impl ThreadWaker {
+    fn blocker(self: &Arc<Self>) -> Blocker {
+        Blocker::Address(Arc::as_ptr(self) as usize)
+    }
+}
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        if let Some(shared_clock) = &self.shared_clock {
+            shared_clock.unblock_all(self.blocker());
+        }
+        self.thread.unpark();
+    }
+}
+
+/// Runs the given future to completion on the current thread. While the
+/// future is pending, the current thread is considered to be blocked, such
+/// that a [`scheduled`](fn.scheduled.html) clock may advance to the earliest
+/// timeout of any pending [`DelayFuture`](struct.DelayFuture.html).
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let thread_waker = Arc::new(ThreadWaker {
+        thread: thread::current(),
+        woken: AtomicBool::new(false),
+        shared_clock: STATE.with(|state| {
+            state
+                .borrow()
+                .as_ref()
+                .map(|state| state.shared_clock.clone())
+        }),
+    });
+    let waker = Waker::from(thread_waker.clone());
+    let mut context = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        let _blocking = BlockingGuard(thread_waker.shared_clock.as_ref().map(|shared_clock| {
+            shared_clock.notify_blocking_unless(thread_waker.blocker(), || {
+                thread_waker.woken.load(Ordering::SeqCst)
+            })
+        }));
+        while !thread_waker.woken.swap(false, Ordering::SeqCst) {
+            thread::park();
+        }
+    }
+}
+
+/// Waker of [`park_timeout`](fn.park_timeout.html) that unparks the parked
+/// thread.
+struct ParkWaker {
+    thread: thread::Thread,
+    woken: AtomicBool,
+    shared_clock: Arc<SharedClock>,
+}
+
+impl Wake for ParkWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::SeqCst);
+        self.shared_clock
+            .unblock_all(Blocker::Park(self.thread.id()));
+        self.thread.unpark();
+    }
+}
+
+/// Parks the current thread until it is [unparked](fn.unpark.html) or until
+/// the given duration plus the [wait jitter](fn.set_wait_jitter.html) has
+/// elapsed on the mocked clock. Returns true if the timeout elapsed. Like a
+/// pending [`DelayFuture`](struct.DelayFuture.html), the timeout elapses
+/// immediately if the clock is neither frozen nor scheduled. Mocks of timed
+/// waits, e.g. of `thread::park_timeout`, call this after
+/// [`spy_timed_wait`](fn.spy_timed_wait.html). As with `thread::park`, the
+/// thread may also return early without being unparked.
+///
+/// Every call is counted as a timed wait in the [statistics](fn.stats.html)
+/// and against the [timed wait limit](fn.set_timed_wait_limit.html).
+///
+/// # Panics
+///
+/// This function panics if the clock is not mocked or if the
+/// [time limit](fn.set_time_limit.html) or the
+/// [timed wait limit](fn.set_timed_wait_limit.html) is exceeded.
+#[track_caller]
+pub fn park_timeout(dur: Duration) -> bool {
+    let started = Instant::now();
+    let local_time =
+        try_get().expect("chronobreak::clock::park_timeout requires the clock to be mocked");
+    let timeout = local_time + dur + wait_jitter();
+    check_limits(timeout, Location::caller(), true);
+    count(|stats| stats.timed_waits += 1);
+    await_turn();
+    record(
+        TraceEventKind::TimedWait { deadline: timeout },
+        local_time,
+        started,
+    );
+    if !is_frozen() && !is_scheduled() {
+        advance_to(timeout);
+        return true;
+    }
+    let park_waker = Arc::new(ParkWaker {
+        thread: thread::current(),
+        woken: AtomicBool::new(false),
+        shared_clock: try_with_local_clock(|state| state.shared_clock.clone())
+            .expect("chronobreak::clock::park_timeout requires the clock to be mocked"),
+    });
+    let shared_clock = &park_waker.shared_clock;
+    let handle = match shared_clock.register_timed_waker(Waker::from(park_waker.clone()), timeout) {
+        Ok((handle, _)) => handle,
+        Err(error) => panic! {"chronobreak::clock::park_timeout failed: {}", error},
+    };
+    if handle.is_some() {
+        let blocker = Blocker::Park(park_waker.thread.id());
+        let _blocking =
+            BlockingGuard(Some(shared_clock.notify_blocking_unless(blocker, || {
+                park_waker.woken.load(Ordering::SeqCst)
+            })));
+        thread::park();
+    }
+    drop(handle);
+    let timed_out = shared_clock.time() >= timeout;
+    if timed_out {
+        let _guard = unfreeze_scoped();
+        advance_to(shared_clock.time());
+    }
+    timed_out
+}
+
+/// Unparks the given thread, e.g. a thread parked by
+/// [`park_timeout`](fn.park_timeout.html), and marks it as runnable for a
+/// [`scheduled`](fn.scheduled.html) clock.
+pub fn unpark(thread: &thread::Thread) {
+    unblock_all(Blocker::Park(thread.id()));
+    thread.unpark();
 }
@@ -102,14 +102,136 @@
 //! when it receives input from one thread while it already entered a timed
 //! wait on another thread.
 //!
+//! # The scheduled clock
+//!
+//! Sleeping threads that advance the clock on their own may observe orderings
+//! that could never happen in reality, e.g. a thread sleeping for five seconds
+//! may finish before a concurrently started thread sleeping for one second.
+//! A clock mocked through [`clock::scheduled`](clock/fn.scheduled.html) or
+//! `#[chronobreak::test(scheduled)]` instead acts as a discrete-event
+//! scheduler: every timed wait blocks until all threads registered to the
+//! clock are blocked. The clock then jumps to the
+//! earliest pending deadline and wakes exactly the threads waiting for it.
+//!
+//! # Clock domains
+//!
+//! All threads spawned from a mocked thread share its clock. To simulate
+//! several nodes with independent clocks in a single test, e.g. a client and
+//! a server, create a [`clock::Domain`](clock/struct.Domain.html) for each of
+//! them. Threads join a domain through
+//! [`Domain::enter`](clock/struct.Domain.html#method.enter) or, with the
+//! `extended-apis` feature, by being spawned through `thread::spawn_in`.
+//! Domains are advanced independently through
+//! [`Domain::advance`](clock/struct.Domain.html#method.advance) or together
+//! through [`clock::advance_domains`](clock/fn.advance_domains.html).
+//!
+//! # Time limits
+//!
+//! A retry loop that sleeps forever does not hang on a mocked clock, it just
+//! races its virtual time towards overflow.
+//! [`clock::set_time_limit`](clock/fn.set_time_limit.html) or
+//! `#[chronobreak::test(max_time = "1h")]` turn such loops into failures: any
+//! sleep or timed wait beyond the limit panics with its call site and the
+//! state of all registered threads.
+//! [`clock::set_timed_wait_limit`](clock/fn.set_timed_wait_limit.html) or
+//! `#[chronobreak::test(max_timed_waits = 1000)]` limit the number of sleeps
+//! and timed waits instead.
+//!
+//! # Busy waiting
+//!
+//! The mocked clock only advances on sleeps and timed waits, so code that
+//! spins on `while Instant::now() < deadline {}` hangs forever.
+//! [`clock::set_auto_tick`](clock/fn.set_auto_tick.html) advances the clock
+//! on every read of the current time, while
+//! [`clock::set_auto_tick_after`](clock/fn.set_auto_tick_after.html) only
+//! does so after a number of consecutive reads without progress.
+//!
+//! # Scheduled callbacks
+//!
+//! Events at precise virtual times, e.g. dropping a connection after ten
+//! seconds, can be injected through
+//! [`clock::schedule_at`](clock/fn.schedule_at.html) and
+//! [`clock::schedule_after`](clock/fn.schedule_after.html). The callback runs
+//! on the thread that advances the shared clock past the given time.
+//!
+//! # Scenarios
+//!
+//! With the `scenario` feature, a test may be driven by a TOML or JSON file
+//! that lists virtual-time steps, e.g. advancing the clock, triggering a hook
+//! or checking an assertion. The test function registers the named hooks and
+//! assertions and chronobreak then executes the timeline, reporting which
+//! step failed. See [`scenario::Scenario`](scenario/struct.Scenario.html) for
+//! the file format.
+//!
+//! ```ignore
+//! #[chronobreak::test(scenario = "tests/scenarios/failover.toml")]
+//! fn failover(scenario: &mut Scenario) {
+//!     scenario.hook("drop_connection", || { /* [...] */ });
+//!     scenario.assertion("failed_over", || { /* [...] */ true });
+//! }
+//! ```
+//!
+//! # Tracing
+//!
+//! [`clock::start_trace`](clock/fn.start_trace.html) records every event of
+//! the shared clock, e.g. sleeps, synchronizations through mocked `Mutex`es,
+//! `Condvar`s and joins, timer registrations and wake-ups and freeze
+//! transitions, together with the thread and the virtual and real time of
+//! each event. With the `chrome-trace` feature, the
+//! [`Trace`](clock/struct.Trace.html) returned by
+//! [`clock::stop_trace`](clock/fn.stop_trace.html) can be exported as Chrome
+//! trace event JSON, which shows the virtual timeline of a failing test in
+//! Perfetto. A flaky multi-threaded test can then be reproduced from its
+//! exported trace with [`clock::replay`](clock/fn.replay.html), which forces
+//! the threads into the recorded order of events and reports the first event
+//! that diverges from the trace.
+//!
+//! # Statistics
+//!
+//! The mocked clock counts the sleeps, the virtual time slept, explicit
+//! advances of the clock, `Condvar` waits, other timed waits, e.g. by
+//! `thread::park_timeout`, timer polls, synchronizations through locks and
+//! joins and reads of the current time of every thread.
+//! [`clock::stats`](clock/fn.stats.html) returns these counters, e.g. to
+//! assert that a poller woke at most three times per minute, and
+//! `#[chronobreak::test(print_stats)]` prints them when the test fails.
+//!
+//! # Spying on waits
+//!
+//! [`clock::spy`](clock/fn.spy.html) records what the code under test asked
+//! to wait for, i.e. every sleep, timer and timed wait with its requested
+//! duration, caller and thread. This verifies e.g. an exponential backoff
+//! instead of just the resulting time through
+//! [`Spy::assert_sleeps`](clock/struct.Spy.html#method.assert_sleeps).
+//!
+//! # Wait jitter
+//!
+//! Real sleeps overshoot, while mocked sleeps advance by exactly the requested
+//! duration. [`clock::set_wait_jitter`](clock/fn.set_wait_jitter.html) lets
+//! every mocked sleep and timer overshoot by a random duration drawn from a
+//! seeded distribution, which exposes code that wrongly assumes sleeps to be
+//! exact while staying reproducible. The seed is printed when the test fails
+//! and can be overridden through the `CHRONOBREAK_JITTER_SEED` environment
+//! variable.
+//!
+
+#![allow(clippy::test_attr_in_doctest)]
 
 extern crate chronobreak_derive;
 
+/// Time-aware assertions.
+pub mod assert;
+
 /// The mocked clock.
 pub mod clock;
 
 /// Mocks for the standard library and several popular crates.
 pub mod mock;
 
+/// Declarative timelines of virtual-time steps.
+#[cfg(feature = "scenario")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scenario")))]
+pub mod scenario;
+
 pub use chronobreak_derive::chronobreak;
 pub use chronobreak_derive::test;
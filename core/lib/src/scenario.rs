@@ -0,0 +1,462 @@
+use crate::clock::{self, Timepoint};
+use chronobreak_globals::duration;
+use serde::Deserialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A single step of a [`Scenario`](struct.Scenario.html).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Step {
+    /// Advances the clock by the given duration.
+    Advance(Duration),
+    /// Checks the assertion with the given name.
+    Assert(String),
+    /// Calls the hook with the given name.
+    Hook(String),
+    /// Freezes the clock on the thread running the scenario.
+    Freeze,
+    /// Unfreezes the clock on the thread running the scenario.
+    Unfreeze,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Advance(dur) => write!(f, "advance by {:?}", dur),
+            Self::Assert(name) => write!(f, "assert `{}`", name),
+            Self::Hook(name) => write!(f, "hook `{}`", name),
+            Self::Freeze => write!(f, "freeze"),
+            Self::Unfreeze => write!(f, "unfreeze"),
+        }
+    }
+}
+
+/// A step as written in a scenario file. Exactly one field must be set.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawStep {
+    advance: Option<String>,
+    assert: Option<String>,
+    hook: Option<String>,
+    #[serde(default)]
+    freeze: bool,
+    #[serde(default)]
+    unfreeze: bool,
+}
+
+impl RawStep {
+    fn parse(self) -> Result<Step, String> {
+        let mut steps = Vec::new();
+        if let Some(dur) = self.advance {
+            steps.push(Step::Advance(duration::parse(&dur)?));
+        }
+        steps.extend(self.assert.map(Step::Assert));
+        steps.extend(self.hook.map(Step::Hook));
+        if self.freeze {
+            steps.push(Step::Freeze);
+        }
+        if self.unfreeze {
+            steps.push(Step::Unfreeze);
+        }
+        match steps.len() {
+            1 => Ok(steps.pop().unwrap()),
+            0 => Err("step does not contain any action".to_string()),
+            _ => Err("step contains more than one action".to_string()),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScenario {
+    steps: Vec<RawStep>,
+}
+
+/// Error returned when a scenario file cannot be parsed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ScenarioError {
+    name: String,
+    message: String,
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid scenario `{}`: {}", self.name, self.message)
+    }
+}
+
+impl Error for ScenarioError {}
+
+/// A timeline of virtual-time steps that drives a test, e.g. loaded from a
+/// TOML or JSON file by `#[chronobreak::test(scenario = "...")]`.
+///
+/// The test registers named [hooks](#method.hook) and
+/// [assertions](#method.assertion), which the steps of the scenario refer to.
+/// [`run`](#method.run) then [schedules](../clock/fn.schedule_at.html) every
+/// hook and assertion at its point in virtual time and advances the clock
+/// through the timeline, such that the steps interleave with the timers and
+/// scheduled callbacks of the code under test.
+///
+/// # Scenario files
+///
+/// A scenario file contains a list of `steps`, each of which contains exactly
+/// one of the following actions:
+///
+/// * `advance = "10s"` advances the clock by the given duration,
+/// * `hook = "name"` calls the hook with the given name,
+/// * `assert = "name"` checks the assertion with the given name,
+/// * `freeze = true` and `unfreeze = true` freeze or unfreeze the clock.
+///
+/// ```toml
+/// [[steps]]
+/// advance = "10s"
+///
+/// [[steps]]
+/// hook = "drop_connection"
+///
+/// [[steps]]
+/// advance = "30s"
+///
+/// [[steps]]
+/// assert = "failed_over"
+/// ```
+///
+/// # Examples
+///
+/// A test usually loads its scenario through the test attribute and only
+/// registers hooks and assertions, after which the scenario is run:
+///
+/// ```ignore
+/// #[chronobreak::test(scenario = "tests/scenarios/failover.toml")]
+/// fn failover(scenario: &mut Scenario) {
+///     let connected = Arc::new(AtomicBool::new(true));
+///     let connection = connected.clone();
+///     scenario.hook("drop_connection", move || connection.store(false, Ordering::SeqCst));
+///     scenario.assertion("failed_over", move || !connected.load(Ordering::SeqCst));
+/// }
+/// ```
+///
+/// Scenarios may also be created and run manually:
+///
+/// ```
+/// # use chronobreak::clock;
+/// # use chronobreak::scenario::Scenario;
+/// let _clock = clock::mock();
+/// let mut scenario = Scenario::from_json(
+///     "timeout",
+///     r#"{"steps": [{"advance": "30s"}, {"assert": "timed_out"}]}"#,
+/// )
+/// .unwrap();
+/// scenario.assertion("timed_out", || clock::get() >= clock::Timepoint::START + std::time::Duration::from_secs(30));
+/// scenario.run();
+/// ```
+pub struct Scenario {
+    name: String,
+    steps: Vec<Step>,
+    registry: Arc<Mutex<Registry>>,
+}
+
+/// The hooks and assertions of a scenario, which are shared with the
+/// scheduled callbacks that call them.
+#[derive(Default)]
+struct Registry {
+    hooks: HashMap<String, Box<dyn FnMut() + Send>>,
+    assertions: HashMap<String, Box<dyn FnMut() -> bool + Send>>,
+}
+
+/// The first step of a scenario that failed.
+struct Failure {
+    index: usize,
+    local_time: Option<Timepoint>,
+    message: String,
+}
+
+impl Scenario {
+    /// Creates a scenario with the given name from the given steps.
+    pub fn new(name: &str, steps: Vec<Step>) -> Self {
+        Self {
+            name: name.to_string(),
+            steps,
+            registry: Arc::default(),
+        }
+    }
+
+    /// Parses a scenario file with the given path and contents. The format is
+    /// determined by the extension of the path, which must be either `toml` or
+    /// `json`.
+    pub fn parse(path: &str, contents: &str) -> Result<Self, ScenarioError> {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(path, contents),
+            Some("json") => Self::from_json(path, contents),
+            _ => Err(ScenarioError {
+                name: path.to_string(),
+                message: "scenario files must have the extension `toml` or `json`".to_string(),
+            }),
+        }
+    }
+
+    /// Parses a scenario in TOML format.
+    pub fn from_toml(name: &str, contents: &str) -> Result<Self, ScenarioError> {
+        let raw = toml::from_str(contents).map_err(|err| ScenarioError {
+            name: name.to_string(),
+            message: err.to_string(),
+        })?;
+        Self::from_raw(name, raw)
+    }
+
+    /// Parses a scenario in JSON format.
+    pub fn from_json(name: &str, contents: &str) -> Result<Self, ScenarioError> {
+        let raw = serde_json::from_str(contents).map_err(|err| ScenarioError {
+            name: name.to_string(),
+            message: err.to_string(),
+        })?;
+        Self::from_raw(name, raw)
+    }
+
+    fn from_raw(name: &str, raw: RawScenario) -> Result<Self, ScenarioError> {
+        let steps = raw
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(index, step)| {
+                step.parse().map_err(|message| ScenarioError {
+                    name: name.to_string(),
+                    message: format!("step {}: {}", index + 1, message),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(name, steps))
+    }
+
+    /// Returns the name of the scenario.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the steps of the scenario.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Registers a hook, which is called by `hook` steps with the given name.
+    pub fn hook<F>(&mut self, name: &str, f: F)
+    where
+        F: FnMut() + Send + 'static,
+    {
+        let mut registry = self.registry.lock().unwrap();
+        registry.hooks.insert(name.to_string(), Box::new(f));
+    }
+
+    /// Registers an assertion, which is checked by `assert` steps with the
+    /// given name. The assertion fails if it returns false or panics.
+    pub fn assertion<F>(&mut self, name: &str, f: F)
+    where
+        F: FnMut() -> bool + Send + 'static,
+    {
+        let mut registry = self.registry.lock().unwrap();
+        registry.assertions.insert(name.to_string(), Box::new(f));
+    }
+
+    /// Executes all steps of the scenario in order.
+    ///
+    /// Every hook and assertion is [scheduled](../clock/fn.schedule_at.html)
+    /// at the local time of the current thread plus the durations of all
+    /// preceding `advance` steps, after which the current thread advances the
+    /// clock step by step. Like other scheduled callbacks, hooks and
+    /// assertions run on the thread that advances the shared clock to their
+    /// time. Freezing and unfreezing only affects the current thread, which
+    /// is why the steps following a `freeze` or `unfreeze` step are only
+    /// scheduled once the current thread reached it.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the clock is not mocked on the current thread,
+    /// if a step refers to a hook or assertion that has not been registered
+    /// or if a step fails. The panic message names the failed step and the
+    /// local time at which it failed.
+    pub fn run(&mut self) {
+        {
+            let registry = self.registry.lock().unwrap();
+            for (index, step) in self.steps.iter().enumerate() {
+                let missing = match step {
+                    Step::Assert(name) if !registry.assertions.contains_key(name) => "assertion",
+                    Step::Hook(name) if !registry.hooks.contains_key(name) => "hook",
+                    _ => continue,
+                };
+                self.fail(
+                    index,
+                    clock::try_get().ok(),
+                    &format!("{} is not registered", missing),
+                );
+            }
+        }
+        let failure = Arc::new(Mutex::new(None));
+        let mut time =
+            clock::try_get().expect("chronobreak::Scenario::run requires the clock to be mocked");
+        let mut batch_start = 0;
+        while batch_start < self.steps.len() {
+            let batch_end = self.steps[batch_start..]
+                .iter()
+                .position(|step| matches!(step, Step::Freeze | Step::Unfreeze))
+                .map_or(self.steps.len(), |position| batch_start + position);
+            time = self.schedule_batch(batch_start..batch_end, time, &failure);
+            self.check(&failure);
+            for index in batch_start..batch_end {
+                if let Step::Advance(dur) = &self.steps[index] {
+                    if let Err(err) = catch_panic(|| clock::advance(*dur)) {
+                        self.fail(index, clock::try_get().ok(), &err);
+                    }
+                    self.check(&failure);
+                }
+            }
+            if let Some(step) = self.steps.get(batch_end) {
+                let frozen = *step == Step::Freeze;
+                if let Err(err) = catch_panic(|| clock::set_frozen(frozen)) {
+                    self.fail(batch_end, clock::try_get().ok(), &err);
+                }
+            }
+            batch_start = batch_end + 1;
+        }
+    }
+
+    /// Schedules the hooks and assertions of the given range of steps, the
+    /// first of which is due at the given time. Steps that are due at the
+    /// same time are called in order by a single callback. Later steps are
+    /// scheduled first, such that steps which are already due, and are thus
+    /// called immediately, cannot let other threads advance the clock past
+    /// steps that have not been scheduled yet. Returns the time at which the
+    /// range ends.
+    fn schedule_batch(
+        &self,
+        range: std::ops::Range<usize>,
+        mut time: Timepoint,
+        failure: &Arc<Mutex<Option<Failure>>>,
+    ) -> Timepoint {
+        let mut groups = Vec::new();
+        let mut due = Vec::new();
+        for index in range {
+            match &self.steps[index] {
+                Step::Advance(dur) => {
+                    groups.push((time, mem::take(&mut due)));
+                    time += *dur;
+                }
+                step => due.push((index, step.clone())),
+            }
+        }
+        groups.push((time, due));
+        for (due_time, steps) in groups.into_iter().rev() {
+            self.schedule_steps(due_time, steps, failure);
+        }
+        time
+    }
+
+    fn schedule_steps(
+        &self,
+        time: Timepoint,
+        steps: Vec<(usize, Step)>,
+        failure: &Arc<Mutex<Option<Failure>>>,
+    ) {
+        if steps.is_empty() {
+            return;
+        }
+        let registry = self.registry.clone();
+        let failure = failure.clone();
+        clock::schedule_at(time, move || {
+            for (index, step) in steps {
+                if failure.lock().unwrap().is_some() {
+                    return;
+                }
+                if let Err(message) = call_step(&registry, &step) {
+                    *failure.lock().unwrap() = Some(Failure {
+                        index,
+                        local_time: clock::try_get().ok(),
+                        message,
+                    });
+                }
+            }
+        });
+    }
+
+    /// Panics with the failure of a scheduled step, if any.
+    fn check(&self, failure: &Mutex<Option<Failure>>) {
+        if let Some(failure) = failure.lock().unwrap().take() {
+            self.fail(failure.index, failure.local_time, &failure.message);
+        }
+    }
+
+    fn fail(&self, index: usize, local_time: Option<Timepoint>, message: &str) -> ! {
+        panic! {
+            "step {} of scenario `{}` ({}) failed at local time {:?}: {}",
+            index + 1,
+            self.name,
+            self.steps[index],
+            local_time,
+            message
+        }
+    }
+}
+
+/// Calls the hook or checks the assertion of the given step. The registry is
+/// not locked during the call, as the step may advance the clock and thereby
+/// run further steps.
+fn call_step(registry: &Mutex<Registry>, step: &Step) -> Result<(), String> {
+    match step {
+        Step::Assert(name) => {
+            let assertion = registry.lock().unwrap().assertions.remove(name);
+            let mut assertion = assertion.ok_or("assertion is already running")?;
+            let result = catch_panic(&mut assertion);
+            registry
+                .lock()
+                .unwrap()
+                .assertions
+                .insert(name.clone(), assertion);
+            match result {
+                Ok(true) => Ok(()),
+                Ok(false) => Err("assertion returned false".to_string()),
+                Err(err) => Err(err),
+            }
+        }
+        Step::Hook(name) => {
+            let hook = registry.lock().unwrap().hooks.remove(name);
+            let mut hook = hook.ok_or("hook is already running")?;
+            let result = catch_panic(&mut hook);
+            registry.lock().unwrap().hooks.insert(name.clone(), hook);
+            result
+        }
+        Step::Advance(_) | Step::Freeze | Step::Unfreeze => Ok(()),
+    }
+}
+
+impl fmt::Debug for Scenario {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Scenario")
+            .field("name", &self.name)
+            .field("steps", &self.steps)
+            .finish()
+    }
+}
+
+/// Calls the given function and returns the message of its panic, if any.
+fn catch_panic<F, R>(f: F) -> Result<R, String>
+where
+    F: FnOnce() -> R,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| panic_message(&*payload))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
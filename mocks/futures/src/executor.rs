@@ -7,9 +7,18 @@ use futures::{
 };
 
 pub use futures::executor::{
-    block_on, block_on_stream, enter, BlockingStream, Enter, EnterError, LocalPool, LocalSpawner,
+    block_on_stream, enter, BlockingStream, Enter, EnterError, LocalPool, LocalSpawner,
 };
 
+/// **Mock** of [`futures::executor::block_on`](https://docs.rs/futures/0.3.6/futures/executor/fn.block_on.html)
+pub fn block_on<F: Future>(f: F) -> F::Output {
+    if clock::is_mocked() {
+        clock::block_on(f)
+    } else {
+        executor::block_on(f)
+    }
+}
+
 /// **Mock** of [`futures::executor::ThreadPoolBuilder`](https://docs.rs/futures/0.3.6/futures/executor/struct.ThreadPoolBuilder.html)
 #[derive(Default, Debug)]
 pub struct ThreadPoolBuilder {
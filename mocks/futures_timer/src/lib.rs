@@ -13,6 +13,7 @@ pub enum Delay {
 }
 
 impl Delay {
+    #[track_caller]
     pub fn new(dur: Duration) -> Self {
         if clock::is_mocked() {
             Self::Mocked(clock::DelayFuture::new(dur))
@@ -21,6 +22,7 @@ impl Delay {
         }
     }
 
+    #[track_caller]
     pub fn reset(&mut self, dur: Duration) {
         match self {
             Self::Actual(delay) => delay.reset(dur),
@@ -1,6 +1,12 @@
+use chronobreak::assert::Timestamp;
 use chronobreak::clock;
+use std::collections::VecDeque;
 use std::fmt;
 use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 pub use parking_lot::{
     const_fair_mutex, const_mutex, const_reentrant_mutex, const_rwlock, lock_api, FairMutex,
@@ -12,6 +18,11 @@ pub use parking_lot::{
 
 struct MutexData<T>(T, clock::SyncHandle);
 
+/// Returns the blocker for threads waiting on the given primitive.
+fn blocker<T>(primitive: &T) -> clock::Blocker {
+    clock::Blocker::Address(primitive as *const T as usize)
+}
+
 impl<T: fmt::Debug> fmt::Debug for MutexData<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
         self.0.fmt(f)
@@ -32,7 +43,14 @@ impl<T> Mutex<T> {
     }
 
     pub fn lock(&self) -> MutexGuard<'_, T> {
-        self.mutex.lock().into()
+        if let Some(guard) = self.mutex.try_lock() {
+            return guard.into();
+        }
+        let guard = {
+            let _blocking = clock::blocking(blocker(&self.mutex));
+            self.mutex.lock()
+        };
+        guard.into()
     }
 
     pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
@@ -73,22 +91,45 @@ impl<'a, T> Drop for MutexGuard<'a, T> {
     fn drop(&mut self) {
         if clock::is_mocked() {
             self.guard.1 = clock::sync_handle();
+            clock::unblock_one(blocker(parking_lot::MutexGuard::mutex(&self.guard)));
         }
     }
 }
 
+/// Returns a `WaitTimeoutResult` with the given value. There is no way to
+/// construct it directly, so a wait on a throwaway condition variable is
+/// used, which only blocks (for a zero timeout) if `timed_out` is set.
+fn wait_timeout_result(timed_out: bool) -> WaitTimeoutResult {
+    let mutex = parking_lot::Mutex::new(());
+    let mut guard = mutex.lock();
+    parking_lot::Condvar::new().wait_while_for(&mut guard, |_| timed_out, Duration::ZERO)
+}
+
+/// A thread in a timed wait on a mocked [`Condvar`](struct.Condvar.html),
+/// which is parked on the mocked clock instead of the real condition
+/// variable.
+struct TimedWaiter {
+    thread: thread::Thread,
+    notified: AtomicBool,
+}
+
+impl TimedWaiter {
+    fn notify(&self) {
+        self.notified.store(true, Ordering::SeqCst);
+        clock::unpark(&self.thread);
+    }
+}
+
 /// **Mock** of [`parking_lot::Condvar`](https://docs.rs/parking_lot/0.11.0/parking_lot/struct.Condvar.html)
 pub struct Condvar {
     condvar: parking_lot::Condvar,
     time_sync: Mutex<()>,
+    timed_waiters: parking_lot::Mutex<VecDeque<Arc<TimedWaiter>>>,
 }
 
 impl Default for Condvar {
     fn default() -> Self {
-        Self {
-            condvar: parking_lot::Condvar::default(),
-            time_sync: Mutex::new(()),
-        }
+        Self::new()
     }
 }
 
@@ -103,6 +144,7 @@ impl Condvar {
         Self {
             condvar: parking_lot::Condvar::new(),
             time_sync: Mutex::new(()),
+            timed_waiters: parking_lot::Mutex::new(VecDeque::new()),
         }
     }
 
@@ -110,7 +152,14 @@ impl Condvar {
         if clock::is_mocked() {
             self.time_sync.lock();
         }
-        let result = self.condvar.notify_one();
+        clock::unblock_one(blocker(self));
+        let mut result = self.condvar.notify_one();
+        if !result {
+            if let Some(waiter) = self.timed_waiters.lock().pop_front() {
+                waiter.notify();
+                result = true;
+            }
+        }
         if clock::is_mocked() {
             self.time_sync.lock();
         }
@@ -121,7 +170,12 @@ impl Condvar {
         if clock::is_mocked() {
             self.time_sync.lock();
         }
-        let result = self.condvar.notify_all();
+        clock::unblock_all(blocker(self));
+        let mut result = self.condvar.notify_all();
+        for waiter in self.timed_waiters.lock().drain(..) {
+            waiter.notify();
+            result += 1;
+        }
         if clock::is_mocked() {
             self.time_sync.lock();
         }
@@ -130,11 +184,67 @@ impl Condvar {
 
     pub fn wait<T>(&self, mutex_guard: &mut MutexGuard<T>) {
         if clock::is_mocked() {
+            clock::count_condvar_wait();
             self.time_sync.lock();
         }
-        self.condvar.wait(&mut mutex_guard.guard);
+        {
+            let _blocking = clock::blocking(blocker(self));
+            clock::unblock_one(blocker(parking_lot::MutexGuard::mutex(&mutex_guard.guard)));
+            self.condvar.wait(&mut mutex_guard.guard);
+        }
         if clock::is_mocked() {
             self.time_sync.lock();
         }
     }
+
+    /// Waits on this condition variable for a notification, timing out
+    /// after the given duration has elapsed on the mocked clock.
+    #[track_caller]
+    pub fn wait_for<T>(
+        &self,
+        mutex_guard: &mut MutexGuard<T>,
+        timeout: Duration,
+    ) -> WaitTimeoutResult {
+        if !clock::is_mocked() {
+            return self.condvar.wait_for(&mut mutex_guard.guard, timeout);
+        }
+        clock::spy_timed_wait(timeout);
+        clock::count_condvar_wait();
+        self.time_sync.lock();
+        let deadline = clock::get() + timeout;
+        let waiter = Arc::new(TimedWaiter {
+            thread: thread::current(),
+            notified: AtomicBool::new(false),
+        });
+        self.timed_waiters.lock().push_back(waiter.clone());
+        clock::unblock_one(blocker(parking_lot::MutexGuard::mutex(&mutex_guard.guard)));
+        parking_lot::MutexGuard::unlocked(&mut mutex_guard.guard, || {
+            while !waiter.notified.load(Ordering::SeqCst) {
+                let remaining = deadline.saturating_duration_since(clock::get());
+                if clock::park_timeout(remaining) {
+                    break;
+                }
+            }
+        });
+        // A waiter that is no longer queued has been notified, even if it
+        // timed out concurrently.
+        let mut timed_waiters = self.timed_waiters.lock();
+        let queued = timed_waiters.iter().position(|w| Arc::ptr_eq(w, &waiter));
+        let timed_out = queued.map(|index| timed_waiters.remove(index)).is_some();
+        drop(timed_waiters);
+        self.time_sync.lock();
+        wait_timeout_result(timed_out)
+    }
+
+    /// Waits on this condition variable for a notification, timing out
+    /// once the given instant of the mocked clock has been reached.
+    #[track_caller]
+    pub fn wait_until<T, I: Timestamp>(
+        &self,
+        mutex_guard: &mut MutexGuard<T>,
+        timeout: I,
+    ) -> WaitTimeoutResult {
+        let remaining = timeout.checked_duration_since(I::now());
+        self.wait_for(mutex_guard, remaining.unwrap_or_default())
+    }
 }
@@ -5,8 +5,7 @@ use std::thread;
 
 #[allow(deprecated)]
 pub use std::thread::{
-    panicking, park, park_timeout, park_timeout_ms, sleep_ms, yield_now, AccessError, LocalKey,
-    Result, ThreadId,
+    panicking, park, sleep_ms, yield_now, AccessError, LocalKey, Result, ThreadId,
 };
 
 /// **Mock** of [`std::thread::Thread`](https://doc.rust-lang.org/std/thread/struct.Thread.html)
@@ -15,7 +14,7 @@ pub struct Thread(thread::Thread);
 
 impl Thread {
     pub fn unpark(&self) {
-        self.0.unpark();
+        clock::unpark(&self.0);
     }
 
     pub fn id(&self) -> ThreadId {
@@ -28,6 +27,7 @@ impl Thread {
 
     #[cfg(feature = "extended-apis")]
     #[cfg_attr(docsrs, doc(cfg(feature = "extended-apis")))]
+    #[track_caller]
     pub fn expect_timed_wait(&self) {
         clock::expect_timed_wait_on(self.0.id())
     }
@@ -39,14 +39,33 @@ pub fn current() -> Thread {
 }
 
 /// **Mock** of [`std::thread::sleep`](https://doc.rust-lang.org/std/thread/fn.sleep.html)
+#[track_caller]
 pub fn sleep(dur: Duration) {
     if clock::is_mocked() {
-        clock::advance(dur);
+        clock::sleep(dur);
     } else {
         thread::sleep(dur);
     }
 }
 
+/// **Mock** of [`std::thread::park_timeout`](https://doc.rust-lang.org/std/thread/fn.park_timeout.html)
+#[track_caller]
+pub fn park_timeout(dur: Duration) {
+    if clock::is_mocked() {
+        clock::spy_timed_wait(dur);
+        clock::park_timeout(dur);
+    } else {
+        thread::park_timeout(dur);
+    }
+}
+
+/// **Mock** of [`std::thread::park_timeout_ms`](https://doc.rust-lang.org/std/thread/fn.park_timeout_ms.html)
+#[deprecated(note = "replaced by `std::thread::park_timeout`")]
+#[track_caller]
+pub fn park_timeout_ms(ms: u32) {
+    park_timeout(Duration::from_millis(u64::from(ms)))
+}
+
 /// **Mock** of [`std::thread::spawn`](https://doc.rust-lang.org/std/thread/fn.spawn.html)
 pub fn spawn<F, T>(f: F) -> JoinHandle<T>
 where
@@ -54,7 +73,28 @@ where
     F: Send + 'static,
     T: Send + 'static,
 {
-    let clock_handle = clock::registration_handle();
+    spawn_with(clock::registration_handle(), f)
+}
+
+/// Spawns a new thread like [`spawn`](fn.spawn.html), but registers it to
+/// the given clock domain instead of the clock of the current thread.
+#[cfg(feature = "extended-apis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "extended-apis")))]
+pub fn spawn_in<F, T>(domain: &clock::Domain, f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T,
+    F: Send + 'static,
+    T: Send + 'static,
+{
+    spawn_with(domain.registration_handle(), f)
+}
+
+fn spawn_with<F, T>(clock_handle: clock::RegistrationHandle, f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T,
+    F: Send + 'static,
+    T: Send + 'static,
+{
     let join_cell = Arc::new(Mutex::new(None));
     let join_cell_weak = Arc::downgrade(&join_cell);
     let barrier = Arc::new(Barrier::new(2));
@@ -86,7 +126,10 @@ impl<T> JoinHandle<T> {
     }
 
     pub fn join(self) -> thread::Result<T> {
-        let result = self.1.join();
+        let result = {
+            let _blocking = clock::blocking(clock::Blocker::Thread(self.2.id()));
+            self.1.join()
+        };
         if clock::is_mocked() {
             if let Some(sync_handle) = *self.0.lock().unwrap() {
                 let _guard = clock::unfreeze_scoped();
@@ -98,6 +141,7 @@ impl<T> JoinHandle<T> {
 
     #[cfg(feature = "extended-apis")]
     #[cfg_attr(docsrs, doc(cfg(feature = "extended-apis")))]
+    #[track_caller]
     pub fn expect_timed_wait(&self) {
         clock::expect_timed_wait_on(self.2.id())
     }
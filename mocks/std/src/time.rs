@@ -3,7 +3,18 @@
 use chronobreak::{clock, mock};
 use std::{cmp, fmt, hash, ops, time};
 
-pub use time::{Duration, SystemTimeError, UNIX_EPOCH};
+pub use time::{Duration, SystemTimeError};
+
+/// Returns a `SystemTimeError` with the given duration. There is no way to
+/// construct it directly.
+fn system_time_error(dur: Duration) -> SystemTimeError {
+    time::UNIX_EPOCH
+        .duration_since(time::UNIX_EPOCH + dur)
+        .unwrap_err()
+}
+
+/// **Mock** of [`std::time::UNIX_EPOCH`](https://doc.rust-lang.org/std/time/constant.UNIX_EPOCH.html)
+pub const UNIX_EPOCH: SystemTime = SystemTime::UNIX_EPOCH;
 
 /// **Mock** of [`std::time::Instant`](https://doc.rust-lang.org/std/time/struct.Instant.html)
 #[derive(Copy, Clone)]
@@ -13,7 +24,7 @@ impl Instant {
     mock::constants![];
 
     pub fn now() -> Self {
-        Self(mock::Mock::new(time::Instant::now, clock::get))
+        Self(mock::Mock::new(time::Instant::now, clock::now))
     }
 
     pub fn duration_since(&self, earlier: Self) -> Duration {
@@ -38,13 +49,23 @@ impl Instant {
     pub fn checked_add(&self, duration: Duration) -> Option<Self> {
         mock::map!(self, |v| v.checked_add(duration))
             .flatten()
-            .map(&Self)
+            .map(Self)
     }
 
     pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
         mock::map!(self, |v| v.checked_sub(duration))
             .flatten()
-            .map(&Self)
+            .map(Self)
+    }
+}
+
+impl chronobreak::assert::Timestamp for Instant {
+    fn now() -> Self {
+        Self::now()
+    }
+
+    fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.checked_duration_since(earlier)
     }
 }
 
@@ -56,7 +77,7 @@ impl Ord for Instant {
 
 impl PartialOrd<Instant> for Instant {
     fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
-        mock::apply!((self, &rhs), |(lhs, rhs)| lhs.partial_cmp(rhs))
+        Some(self.cmp(rhs))
     }
 }
 
@@ -131,14 +152,16 @@ impl SystemTime {
     )];
 
     pub fn now() -> Self {
-        Self(mock::Mock::new(time::SystemTime::now, clock::get))
+        Self(mock::Mock::new(time::SystemTime::now, clock::system_time))
     }
 
     pub fn duration_since(&self, earlier: Self) -> Result<Duration, SystemTimeError> {
         mock::apply!(
             (self, earlier),
             |(now, earlier)| now.duration_since(earlier),
-            |(now, earlier)| Ok(now.duration_since(earlier))
+            |(now, earlier)| now
+                .checked_duration_since(earlier)
+                .ok_or_else(|| system_time_error(earlier.duration_since(now)))
         )
     }
 
@@ -149,13 +172,23 @@ impl SystemTime {
     pub fn checked_add(&self, duration: Duration) -> Option<Self> {
         mock::map!(self, |v| v.checked_add(duration))
             .flatten()
-            .map(&Self)
+            .map(Self)
     }
 
     pub fn checked_sub(&self, duration: Duration) -> Option<Self> {
         mock::map!(self, |v| v.checked_sub(duration))
             .flatten()
-            .map(&Self)
+            .map(Self)
+    }
+}
+
+impl chronobreak::assert::Timestamp for SystemTime {
+    fn now() -> Self {
+        Self::now()
+    }
+
+    fn checked_duration_since(&self, earlier: Self) -> Option<Duration> {
+        self.duration_since(earlier).ok()
     }
 }
 
@@ -167,7 +200,7 @@ impl Ord for SystemTime {
 
 impl PartialOrd<SystemTime> for SystemTime {
     fn partial_cmp(&self, rhs: &Self) -> Option<cmp::Ordering> {
-        mock::apply!((self, &rhs), |(lhs, rhs)| lhs.partial_cmp(rhs))
+        Some(self.cmp(rhs))
     }
 }
 
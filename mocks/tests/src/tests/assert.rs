@@ -0,0 +1,73 @@
+use chronobreak::{assert_between, assert_elapsed, assert_no_time_passed, assert_now};
+#[chronobreak]
+mod mock {
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+#[chronobreak::test]
+fn assert_elapsed_is_exact() {
+    let start = Instant::now();
+    thread::sleep(Duration::from_secs(1));
+    assert_elapsed! {start, Duration::from_secs(1)};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "delta:     +1ms")]
+fn assert_elapsed_reports_delta() {
+    let start = Instant::now();
+    thread::sleep(Duration::from_millis(1001));
+    assert_elapsed! {start, Duration::from_secs(1)};
+}
+
+#[chronobreak::test]
+fn assert_elapsed_accepts_tolerance() {
+    let start = SystemTime::now();
+    thread::sleep(Duration::from_millis(1001));
+    assert_elapsed! {start, Duration::from_secs(1), Duration::from_millis(1)};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "delta:     -1s")]
+fn assert_now_reports_negative_delta() {
+    let expected = Instant::now() + Duration::from_secs(2);
+    thread::sleep(Duration::from_secs(1));
+    assert_now! {expected};
+}
+
+#[chronobreak::test]
+fn assert_between_accepts_bounds() {
+    let start = Instant::now();
+    thread::sleep(Duration::from_secs(1));
+    assert_between! {start, Duration::from_secs(1), Duration::from_secs(2)};
+    thread::sleep(Duration::from_secs(1));
+    assert_between! {start, Duration::from_secs(1), Duration::from_secs(2)};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "expected:")]
+fn assert_between_panics_outside_bounds() {
+    let start = Instant::now();
+    thread::sleep(Duration::from_millis(500));
+    assert_between! {start, Duration::from_secs(1), Duration::from_secs(2)};
+}
+
+#[chronobreak::test]
+fn assert_no_time_passed_returns_result() {
+    assert_eq! {assert_no_time_passed!(|| 42), 42};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "delta:     +1s")]
+fn assert_no_time_passed_panics_on_sleep() {
+    assert_no_time_passed! {|| thread::sleep(Duration::from_secs(1))};
+}
+
+#[test]
+fn assertions_use_default_tolerance_for_actual_clock() {
+    let start = Instant::now();
+    assert_elapsed! {start, Duration::from_millis(0)};
+    assert_now! {start};
+    assert_no_time_passed! {|| ()};
+}
@@ -43,3 +43,478 @@ fn mock_is_not_global() {
     .unwrap();
     assert_eq! {Instant::now(), start_time};
 }
+
+#[chronobreak::test]
+fn suspend_advances_system_and_boot_time() {
+    let instant = Instant::now();
+    let system_time = SystemTime::now();
+    let boot_time = clock::boot_time();
+    clock::suspend_for(Duration::from_secs(60));
+    assert_eq! {Instant::now(), instant};
+    assert_eq! {system_time.elapsed().unwrap(), Duration::from_secs(60)};
+    assert_eq! {clock::boot_time(), boot_time + Duration::from_secs(60)};
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {instant.elapsed(), Duration::from_secs(1)};
+    assert_eq! {system_time.elapsed().unwrap(), Duration::from_secs(61)};
+    assert_eq! {clock::boot_time(), boot_time + Duration::from_secs(61)};
+}
+
+#[chronobreak::test(frozen)]
+fn suspend_does_not_complete_delays() {
+    use futures::task::{noop_waker_ref, Context};
+    use std::future::Future;
+    use std::pin::Pin;
+    let mut delay = clock::DelayFuture::new(Duration::from_secs(1));
+    let mut context = Context::from_waker(noop_waker_ref());
+    assert! {Pin::new(&mut delay).poll(&mut context).is_pending()};
+    clock::suspend_for(Duration::from_secs(3600));
+    assert! {Pin::new(&mut delay).poll(&mut context).is_pending()};
+}
+
+#[chronobreak::test]
+fn skew_only_affects_the_given_thread() {
+    clock::advance(Duration::from_secs(10));
+    let instant = Instant::now();
+    let system_time = SystemTime::now();
+    clock::set_skew(
+        thread::current().id(),
+        clock::Skew::Ahead(Duration::from_secs(5)),
+    );
+    assert_eq! {instant.elapsed(), Duration::from_secs(5)};
+    assert_eq! {system_time.elapsed().unwrap(), Duration::from_secs(5)};
+    thread::spawn(move || {
+        assert_eq! {Instant::now(), instant};
+    })
+    .join()
+    .unwrap();
+    clock::set_skew(
+        thread::current().id(),
+        clock::Skew::Behind(Duration::from_secs(5)),
+    );
+    assert_eq! {instant.duration_since(Instant::now()), Duration::from_secs(5)};
+}
+
+#[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+fn skew_behind_applies_from_the_start() {
+    let instant = Instant::now();
+    let system_time = SystemTime::now();
+    clock::set_skew(
+        thread::current().id(),
+        clock::Skew::Behind(Duration::from_secs(5)),
+    );
+    assert_eq! {instant.duration_since(Instant::now()), Duration::from_secs(5)};
+    assert_eq! {system_time.duration_since(SystemTime::now()).unwrap(), Duration::from_secs(5)};
+}
+
+#[chronobreak::test]
+fn drift_changes_the_rate_of_the_observed_time() {
+    let instant = Instant::now();
+    clock::set_drift(thread::current().id(), 1_000);
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {instant.elapsed(), Duration::from_millis(1_001)};
+    clock::set_drift(thread::current().id(), 0);
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {instant.elapsed(), Duration::from_millis(2_001)};
+}
+
+#[chronobreak::test]
+fn sleeps_synchronize_despite_skew() {
+    let main_start = Instant::now();
+    let thread = thread::spawn(move || {
+        clock::set_skew(
+            thread::current().id(),
+            clock::Skew::Ahead(Duration::from_secs(10)),
+        );
+        let start = Instant::now();
+        thread::sleep(Duration::from_secs(1));
+        assert_eq! {start.elapsed(), Duration::from_secs(1)};
+        start
+    });
+    let start = thread.join().unwrap();
+    assert_eq! {start.duration_since(main_start), Duration::from_secs(10)};
+    assert_eq! {main_start.elapsed(), Duration::from_secs(1)};
+}
+
+#[test]
+fn timed_waiters_lists_frozen_threads() {
+    let _clock = clock::frozen();
+    let main_thread = thread::current();
+    let thread = thread::spawn(move || {
+        clock::expect_timed_wait_on(main_thread.id());
+        let deadline = clock::Timepoint::START + Duration::from_secs(1);
+        assert_eq! {clock::timed_waiters(), vec![clock::TimedWaiter {
+            thread: main_thread.id(),
+            deadline,
+        }]};
+        assert_eq! {clock::next_deadline(), Some(deadline)};
+        clock::advance(Duration::from_secs(1));
+    });
+    clock::advance(Duration::from_secs(1));
+    thread.join().unwrap();
+    assert! {clock::timed_waiters().is_empty()};
+    assert_eq! {clock::next_deadline(), None};
+}
+
+#[chronobreak::test(frozen)]
+fn pending_timers_lists_delays() {
+    use futures::task::{noop_waker_ref, Context};
+    use std::future::Future;
+    use std::pin::Pin;
+    let mut delay = clock::DelayFuture::new(Duration::from_secs(30));
+    let mut context = Context::from_waker(noop_waker_ref());
+    assert! {Pin::new(&mut delay).poll(&mut context).is_pending()};
+    let deadline = clock::Timepoint::START + Duration::from_secs(30);
+    assert_eq! {clock::pending_timers(), vec![clock::PendingTimer {
+        deadline,
+        owner: thread::current().id(),
+    }]};
+    assert_eq! {clock::next_deadline(), Some(deadline)};
+    drop(delay);
+    assert! {clock::pending_timers().is_empty()};
+    assert_eq! {clock::next_deadline(), None};
+}
+
+fn frozen_domain() -> clock::Domain {
+    clock::Domain::with_config(
+        "frozen",
+        clock::Config {
+            frozen: true,
+            ..Default::default()
+        },
+    )
+}
+
+fn spawn_sleeper(
+    domain: &clock::Domain,
+    sleeps: Vec<Duration>,
+) -> ::std::thread::JoinHandle<Vec<Instant>> {
+    let domain = domain.clone();
+    let barrier = std::sync::Arc::new(std::sync::Barrier::new(2));
+    let barrier2 = barrier.clone();
+    let handle = ::std::thread::spawn(move || {
+        let _domain = domain.enter();
+        barrier2.wait();
+        sleeps
+            .into_iter()
+            .map(|dur| {
+                thread::sleep(dur);
+                Instant::now()
+            })
+            .collect()
+    });
+    barrier.wait();
+    handle
+}
+
+#[test]
+fn advance_to_next_timer_completes_earliest_wait() {
+    let domain = frozen_domain();
+    let _domain = domain.enter();
+    let start_time = Instant::now();
+    assert_eq! {clock::advance_to_next_timer(), None};
+    let slow = spawn_sleeper(&domain, vec![Duration::from_secs(30)]);
+    let fast = spawn_sleeper(&domain, vec![Duration::from_secs(10)]);
+    assert_eq! {clock::advance_to_next_timer(), Some(clock::Timepoint::START + Duration::from_secs(10))};
+    assert_eq! {fast.join().unwrap(), vec![start_time + Duration::from_secs(10)]};
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(10)};
+    assert_eq! {clock::advance_to_next_timer(), Some(clock::Timepoint::START + Duration::from_secs(30))};
+    assert_eq! {slow.join().unwrap(), vec![start_time + Duration::from_secs(30)]};
+}
+
+#[test]
+fn run_until_idle_stops_at_limit() {
+    let domain = frozen_domain();
+    let _domain = domain.enter();
+    let start_time = Instant::now();
+    let sleeper = spawn_sleeper(&domain, vec![Duration::from_secs(1); 5]);
+    assert! {!clock::run_until_idle(Duration::from_secs(2))};
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(2)};
+    assert! {clock::run_until_idle(Duration::from_secs(3600))};
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(5)};
+    let expected: Vec<_> = (1..=5)
+        .map(|secs| start_time + Duration::from_secs(secs))
+        .collect();
+    assert_eq! {sleeper.join().unwrap(), expected};
+}
+
+#[chronobreak::test(frozen)]
+fn advance_to_next_timer_is_traced_and_counted() {
+    clock::schedule_after(Duration::from_secs(10), || {});
+    clock::start_trace();
+    let time = clock::Timepoint::START + Duration::from_secs(10);
+    assert_eq! {clock::advance_to_next_timer(), Some(time)};
+    assert_eq! {clock::stop_trace().events()[0].kind, clock::TraceEventKind::Advance { to: time }};
+    let stats = clock::stats().thread(thread::current().id());
+    assert_eq! {stats.advances, 1};
+    assert_eq! {stats.advanced, Duration::from_secs(10)};
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "beyond the time limit")]
+fn advance_to_next_timer_respects_time_limit() {
+    clock::set_time_limit(Duration::from_secs(5));
+    clock::schedule_after(Duration::from_secs(10), || {});
+    clock::advance_to_next_timer();
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "thread 'tests::clock::frozen_deadlock_is_reported'")]
+fn frozen_deadlock_is_reported() {
+    thread::sleep(Duration::from_secs(1));
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "timed wait at local time 0ns until 1s")]
+fn frozen_deadlock_is_detected_on_thread_exit() {
+    let thread = thread::spawn(|| {});
+    thread::sleep(Duration::from_secs(1));
+    thread.join().unwrap();
+}
+
+#[chronobreak::test(frozen)]
+fn frozen_deadlock_is_recovered_from() {
+    let result = std::panic::catch_unwind(|| thread::sleep(Duration::from_secs(1)));
+    assert! {result.is_err()};
+    let main_thread = thread::current();
+    let thread = thread::spawn(move || {
+        main_thread.expect_timed_wait();
+        clock::advance(Duration::from_secs(2));
+    });
+    thread::sleep(Duration::from_secs(2));
+    thread.join().unwrap();
+    assert_eq! {clock::get(), clock::Timepoint::START + Duration::from_secs(3)};
+}
+
+#[chronobreak::test(frozen)]
+fn try_expect_timed_wait_on_times_out() {
+    let (sender, receiver) = std::sync::mpsc::channel::<()>();
+    let thread = thread::spawn(move || receiver.recv().unwrap());
+    let error =
+        match clock::try_expect_timed_wait_on(thread.thread().id(), Duration::from_millis(10)) {
+            Err(clock::ClockError::ExpectTimeout(error)) => error,
+            result => panic! {"unexpected result {:?}", result},
+        };
+    assert_eq! {error.thread, thread.thread().id()};
+    assert_eq! {error.activity, "runnable, it did not perform any timed wait yet"};
+    sender.send(()).unwrap();
+    thread.join().unwrap();
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "did not enter a timed wait within 10ms of real time; it is runnable")]
+fn expect_timed_wait_on_times_out() {
+    clock::set_expect_timeout(Duration::from_millis(10));
+    let (_sender, receiver) = std::sync::mpsc::channel::<()>();
+    let thread = thread::spawn(move || receiver.recv().unwrap_err());
+    thread.expect_timed_wait();
+}
+
+#[test]
+fn expect_timed_waits_on_synchronizes_each_iteration() {
+    let _clock = clock::frozen();
+    let main_thread = thread::current();
+    let thread = thread::spawn(move || {
+        for i in 1..=3 {
+            let info = clock::expect_timed_waits_on(main_thread.id(), i);
+            assert_eq! {info, clock::TimedWaitInfo {
+                deadline: clock::Timepoint::START + Duration::from_secs(i),
+                started_at: clock::Timepoint::START + Duration::from_secs(i - 1),
+            }};
+            assert_eq! {clock::timed_wait_generation(main_thread.id()), i};
+            clock::advance(Duration::from_secs(1));
+        }
+    });
+    for _ in 0..3 {
+        thread::sleep(Duration::from_secs(1));
+    }
+    thread.join().unwrap();
+}
+
+#[test]
+fn expect_next_timed_wait_on_ignores_current_wait() {
+    let _clock = clock::frozen();
+    let main_thread = thread::current();
+    let thread = thread::spawn(move || {
+        clock::expect_timed_wait_on(main_thread.id());
+        clock::set_expect_timeout(Duration::from_millis(10));
+        let next = std::panic::catch_unwind(|| clock::expect_next_timed_wait_on(main_thread.id()));
+        assert! {next.is_err()};
+        clock::advance(Duration::from_secs(1));
+        let info = clock::expect_timed_waits_on(main_thread.id(), 2);
+        clock::advance(Duration::from_secs(1));
+        info
+    });
+    thread::sleep(Duration::from_secs(1));
+    thread::sleep(Duration::from_secs(1));
+    assert_eq! {thread.join().unwrap().deadline, clock::Timepoint::START + Duration::from_secs(2)};
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "only 0 of 1 expected threads entered a timed wait")]
+fn expect_timed_waits_times_out() {
+    clock::set_expect_timeout(Duration::from_millis(10));
+    clock::expect_timed_waits(1);
+}
+
+#[test]
+fn try_api_reports_errors() {
+    assert_eq! {clock::try_get(), Err(clock::ClockError::NotMocked)};
+    assert_eq! {clock::try_now(), Err(clock::ClockError::NotMocked)};
+    assert_eq! {clock::try_advance(Duration::from_secs(1)), Err(clock::ClockError::NotMocked)};
+    assert! {clock::try_unfreeze_scoped().is_err()};
+    assert! {clock::DelayFuture::try_new(Duration::from_secs(1)).is_err()};
+    let _clock = clock::try_mock().unwrap();
+    assert! {matches!(clock::try_mock(), Err(clock::ClockError::AlreadyMocked))};
+    assert_eq! {clock::try_advance(Duration::from_secs(1)), Ok(())};
+    assert_eq! {clock::try_get(), Ok(clock::Timepoint::START + Duration::from_secs(1))};
+    let unregistered = std::thread::spawn(|| {}).thread().id();
+    assert_eq! {
+        clock::try_timed_wait_generation(unregistered),
+        Err(clock::ClockError::ThreadNotRegistered(unregistered))
+    };
+    assert_eq! {
+        clock::try_expect_timed_wait_on(unregistered, Duration::from_millis(10)),
+        Err(clock::ClockError::ThreadNotRegistered(unregistered))
+    };
+}
+
+#[test]
+fn try_sync_with_reports_mixed_handles() {
+    let unmocked = clock::sync_handle();
+    let _clock = clock::mock();
+    assert_eq! {clock::try_sync_with(unmocked), Err(clock::ClockError::MixedMockedAndActual)};
+    let mocked = clock::sync_handle();
+    assert_eq! {clock::try_sync_with(mocked), Ok(())};
+    let unmocked_thread = ::std::thread::spawn(move || clock::try_sync_with(mocked));
+    assert_eq! {unmocked_thread.join().unwrap(), Err(clock::ClockError::MixedMockedAndActual)};
+}
+
+#[test]
+#[should_panic(expected = "mocked and non-mocked values may not be mixed")]
+fn mixing_mocked_and_actual_values_panics() {
+    let instant = Instant::now();
+    let _clock = clock::mock();
+    let _ = instant.elapsed();
+}
+
+#[chronobreak::test(max_time = "1h")]
+fn sleeping_within_time_limit() {
+    thread::sleep(Duration::from_secs(3600));
+}
+
+#[chronobreak::test(max_time = "1h")]
+#[should_panic(expected = "beyond the time limit of 3600s")]
+fn sleep_loop_exceeds_time_limit() {
+    loop {
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "tests/clock.rs")]
+fn time_limit_reports_call_site() {
+    clock::set_time_limit(Duration::from_secs(1));
+    thread::spawn(|| thread::sleep(Duration::from_secs(2)))
+        .join()
+        .unwrap_or_else(|err| std::panic::resume_unwind(err));
+}
+
+#[chronobreak::test(max_timed_waits = 10)]
+#[should_panic(expected = "more than 10 timed waits")]
+fn zero_sleep_loop_exceeds_timed_wait_limit() {
+    loop {
+        thread::sleep(Duration::from_secs(0));
+    }
+}
+
+#[chronobreak::test(max_timed_waits = 10)]
+fn explicit_advances_dont_count_as_timed_waits() {
+    for _ in 0..20 {
+        clock::advance(Duration::from_secs(1));
+    }
+    thread::sleep(Duration::from_secs(1));
+    assert_eq! {clock::get(), clock::Timepoint::START + Duration::from_secs(21)};
+}
+
+#[chronobreak::test(max_time = "1h")]
+#[should_panic(expected = "beyond the time limit of 3600s")]
+fn explicit_advance_exceeds_time_limit() {
+    clock::advance(Duration::from_secs(3601));
+}
+
+#[chronobreak::test(frozen)]
+#[should_panic(expected = "beyond the time limit")]
+fn delay_future_exceeds_time_limit() {
+    clock::set_time_limit(Duration::from_secs(1));
+    futures::executor::block_on(clock::DelayFuture::new(Duration::from_secs(2)));
+}
+
+#[chronobreak::test]
+fn auto_tick_breaks_busy_wait() {
+    clock::set_auto_tick(Duration::from_millis(1));
+    let deadline = Instant::now() + Duration::from_millis(10);
+    while Instant::now() < deadline {}
+    assert_eq! {clock::get(), clock::Timepoint::START + Duration::from_millis(11)};
+}
+
+#[chronobreak::test(frozen)]
+fn auto_tick_advances_frozen_clock() {
+    clock::set_auto_tick(Duration::from_millis(1));
+    thread::spawn(|| {
+        let deadline = SystemTime::now() + Duration::from_millis(10);
+        while SystemTime::now() < deadline {}
+    })
+    .join()
+    .unwrap();
+    assert_eq! {clock::get(), clock::Timepoint::START + Duration::from_millis(11)};
+}
+
+#[chronobreak::test]
+fn auto_tick_after_reads_without_progress() {
+    clock::set_auto_tick_after(3, Duration::from_secs(1));
+    let start = Instant::now();
+    assert_eq! {Instant::now(), start};
+    assert_eq! {Instant::now(), start};
+    assert_eq! {Instant::now(), start + Duration::from_secs(1)};
+    thread::sleep(Duration::from_secs(1));
+    assert_eq! {Instant::now(), start + Duration::from_secs(2)};
+    clock::set_auto_tick(Duration::from_secs(0));
+    assert_eq! {Instant::now(), Instant::now()};
+}
+
+#[chronobreak::test]
+fn scheduled_callback_runs_when_clock_crosses_time() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    clock::schedule_at(
+        clock::Timepoint::START + Duration::from_secs(10),
+        move || sender.send(clock::get()).unwrap(),
+    );
+    assert_eq! {clock::pending_timers().len(), 1};
+    thread::sleep(Duration::from_secs(9));
+    assert! {receiver.try_recv().is_err()};
+    thread::sleep(Duration::from_secs(2));
+    assert_eq! {receiver.try_recv(), Ok(clock::Timepoint::START + Duration::from_secs(11))};
+    assert! {clock::pending_timers().is_empty()};
+}
+
+#[chronobreak::test]
+fn scheduled_callback_in_the_past_runs_immediately() {
+    clock::advance(Duration::from_secs(1));
+    let (sender, receiver) = std::sync::mpsc::channel();
+    clock::schedule_at(clock::Timepoint::START, move || sender.send(()).unwrap());
+    assert_eq! {receiver.try_recv(), Ok(())};
+}
+
+#[chronobreak::test(frozen)]
+fn run_until_idle_runs_scheduled_callbacks() {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    for secs in [3, 1, 2] {
+        let sender = sender.clone();
+        clock::schedule_after(Duration::from_secs(secs), move || {
+            sender.send(secs).unwrap()
+        });
+    }
+    assert! {clock::run_until_idle(Duration::from_secs(60))};
+    assert_eq! {receiver.try_iter().collect::<Vec<_>>(), vec![1, 2, 3]};
+    assert_eq! {clock::get(), clock::Timepoint::START + Duration::from_secs(3)};
+}
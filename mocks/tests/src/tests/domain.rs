@@ -0,0 +1,96 @@
+use chronobreak::clock::{self, Config, Domain, Timepoint};
+#[chronobreak]
+mod mock {
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+use std::sync::mpsc;
+
+#[chronobreak::test]
+fn enter_restores_previous_clock() {
+    clock::advance(Duration::from_secs(1));
+    let domain = Domain::new("node-a");
+    {
+        let _domain = domain.enter();
+        assert_eq! {clock::get(), Timepoint::START};
+        clock::advance(Duration::from_secs(5));
+    }
+    assert_eq! {clock::get(), Timepoint::START + Duration::from_secs(1)};
+    assert_eq! {domain.time(), Timepoint::START + Duration::from_secs(5)};
+}
+
+#[chronobreak::test]
+fn join_does_not_synchronize_domains() {
+    let start_time = Instant::now();
+    let server = Domain::new("server");
+    let elapsed = thread::spawn_in(&server, || {
+        let start_time = Instant::now();
+        thread::sleep(Duration::from_secs(10));
+        start_time.elapsed()
+    })
+    .join()
+    .unwrap();
+    assert_eq! {elapsed, Duration::from_secs(10)};
+    assert_eq! {server.time(), Timepoint::START + Duration::from_secs(10)};
+    assert_eq! {Instant::now(), start_time};
+}
+
+#[test]
+fn domains_advance_together() {
+    let frozen = Domain::with_config(
+        "frozen",
+        Config {
+            frozen: true,
+            ..Default::default()
+        },
+    );
+    let other = Domain::new("other");
+    let (sender, receiver) = mpsc::channel();
+    let domain = frozen.clone();
+    let handle = ::std::thread::spawn(move || {
+        let _domain = domain.enter();
+        let start_time = Instant::now();
+        sender.send(()).unwrap();
+        thread::sleep(Duration::from_secs(1));
+        start_time.elapsed()
+    });
+    receiver.recv().unwrap();
+    clock::advance_domains(&[&frozen, &other], Duration::from_secs(1));
+    assert_eq! {handle.join().unwrap(), Duration::from_secs(1)};
+    assert_eq! {other.time(), Timepoint::START + Duration::from_secs(1)};
+}
+
+#[test]
+fn expect_timed_waits_counts_named_threads() {
+    let domain = Domain::with_config(
+        "frozen",
+        Config {
+            frozen: true,
+            ..Default::default()
+        },
+    );
+    let _domain = domain.enter();
+    let threads: Vec<_> = ["worker-0", "worker-1", "worker-2", "other"]
+        .iter()
+        .map(|name| {
+            let domain = domain.clone();
+            ::std::thread::Builder::new()
+                .name(name.to_string())
+                .spawn(move || {
+                    let _domain = domain.enter();
+                    thread::sleep(Duration::from_secs(1));
+                })
+                .unwrap()
+        })
+        .collect();
+    clock::expect_timed_waits_with_prefix("worker-", 3);
+    clock::expect_timed_waits(4);
+    clock::expect_all_timed_waiting_with_prefix("worker-");
+    clock::expect_all_timed_waiting();
+    assert_eq! {clock::timed_waiters().len(), 4};
+    domain.advance(Duration::from_secs(1));
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
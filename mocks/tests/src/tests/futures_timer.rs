@@ -1,4 +1,6 @@
 mod delay {
+    #[chronobreak]
+    use futures::executor::block_on;
     use futures::task::{waker, ArcWake, Context, Poll};
     #[chronobreak]
     use futures_timer::*;
@@ -90,4 +92,48 @@ mod delay {
         futures::executor::block_on(Delay::new(Duration::from_nanos(1)));
         thread.join().unwrap();
     }
+
+    #[chronobreak::test(scheduled)]
+    async fn scheduled_join_completes_in_deadline_order() {
+        let start_time = Instant::now();
+        let (slow, fast) = futures::join!(
+            async {
+                Delay::new(Duration::from_secs(5)).await;
+                Instant::now()
+            },
+            async {
+                Delay::new(Duration::from_secs(1)).await;
+                Instant::now()
+            }
+        );
+        assert_eq! {fast, start_time + Duration::from_secs(1)};
+        assert_eq! {slow, start_time + Duration::from_secs(5)};
+    }
+
+    #[chronobreak::test(scheduled)]
+    async fn scheduled_select_completes_earliest_event() {
+        let start_time = Instant::now();
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_secs(1));
+            sender.send(()).unwrap();
+        });
+        let delay = Delay::new(Duration::from_secs(5));
+        let result = futures::future::select(delay, receiver).await;
+        assert! {matches!(result, futures::future::Either::Right(_))};
+        assert_eq! {Instant::now(), start_time + Duration::from_secs(1)};
+        thread.join().unwrap();
+    }
+
+    #[chronobreak::test(scheduled)]
+    fn scheduled_block_on_is_blocking() {
+        let start_time = Instant::now();
+        let thread = thread::spawn(move || {
+            thread::sleep(Duration::from_secs(1));
+            Instant::now()
+        });
+        block_on(Delay::new(Duration::from_secs(2)));
+        assert_eq! {Instant::now(), start_time + Duration::from_secs(2)};
+        assert_eq! {thread.join().unwrap(), start_time + Duration::from_secs(1)};
+    }
 }
@@ -0,0 +1,95 @@
+use chronobreak::clock::Distribution;
+
+#[chronobreak]
+mod mock {
+    pub use futures_timer::Delay;
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+fn uniform() -> Distribution {
+    Distribution::Uniform {
+        min: Duration::from_millis(1),
+        max: Duration::from_millis(10),
+    }
+}
+
+/// Returns the actual durations of the given number of one second sleeps.
+fn sleeps(count: usize) -> Vec<Duration> {
+    (0..count)
+        .map(|_| {
+            let start = Instant::now();
+            thread::sleep(Duration::from_secs(1));
+            start.elapsed()
+        })
+        .collect()
+}
+
+#[chronobreak::test]
+fn jitter_overshoots_sleeps() {
+    clock::set_wait_jitter(uniform(), 1);
+    for slept in sleeps(100) {
+        assert! {slept >= Duration::from_millis(1001), "{:?}", slept};
+        assert! {slept <= Duration::from_millis(1010), "{:?}", slept};
+    }
+}
+
+#[test]
+fn jitter_is_reproducible() {
+    let run = || {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::set_wait_jitter(uniform(), 7);
+        sleeps(10)
+    };
+    let first = run();
+    assert_eq! {run(), first};
+    assert! {first.iter().any(|slept| *slept != first[0])};
+}
+
+#[chronobreak::test]
+fn jitter_does_not_affect_advance() {
+    clock::set_wait_jitter(Distribution::Constant(Duration::from_millis(5)), 1);
+    let start = Instant::now();
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {start.elapsed(), Duration::from_secs(1)};
+    clock::clear_wait_jitter();
+    assert_eq! {sleeps(1), vec![Duration::from_secs(1)]};
+    assert_eq! {clock::wait_jitter_seed(), None};
+}
+
+#[chronobreak::test]
+async fn jitter_overshoots_delays() {
+    clock::set_wait_jitter(Distribution::Constant(Duration::from_millis(5)), 1);
+    let spy = clock::spy();
+    let start = Instant::now();
+    Delay::new(Duration::from_secs(1)).await;
+    assert_eq! {start.elapsed(), Duration::from_millis(1005)};
+    spy.assert_sleeps(&[Duration::from_secs(1)]);
+}
+
+#[chronobreak::test]
+fn exponential_jitter_has_given_mean() {
+    clock::set_wait_jitter(
+        Distribution::Exponential {
+            mean: Duration::from_millis(1),
+        },
+        3,
+    );
+    let overshoot: Duration = sleeps(1000)
+        .into_iter()
+        .map(|slept| slept - Duration::from_secs(1))
+        .sum();
+    let mean = overshoot / 1000;
+    assert! {mean > Duration::from_micros(800), "{:?}", mean};
+    assert! {mean < Duration::from_micros(1200), "{:?}", mean};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "sleep was not exact")]
+fn jitter_breaks_exact_sleep_assumptions() {
+    clock::set_wait_jitter(uniform(), 1);
+    let start = Instant::now();
+    thread::sleep(Duration::from_secs(1));
+    assert! {start.elapsed() == Duration::from_secs(1), "sleep was not exact"};
+}
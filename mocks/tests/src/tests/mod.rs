@@ -1,5 +1,13 @@
+mod assert;
 mod clock;
+mod domain;
 mod futures;
 mod futures_timer;
+mod jitter;
 mod parking_lot;
+mod scenario;
+mod scheduled;
+mod spy;
+mod stats;
 mod std;
+mod trace;
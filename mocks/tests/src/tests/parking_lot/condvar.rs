@@ -70,3 +70,51 @@ fn advances_notifier_impl() {
     data2.1.notify_all();
     assert_eq! {Instant::now(), start_time + Duration::from_millis(1)}
 }
+
+#[chronobreak::test]
+fn wait_for_times_out() {
+    let start_time = Instant::now();
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let mut lock = mutex.lock();
+    assert! {condvar.wait_for(&mut lock, Duration::from_secs(1)).timed_out()};
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(1)};
+    let deadline = Instant::now() + Duration::from_secs(1);
+    assert! {condvar.wait_until(&mut lock, deadline).timed_out()};
+    assert_eq! {Instant::now(), deadline};
+}
+
+#[chronobreak::test(scheduled)]
+fn wait_for_is_notified() {
+    let start_time = Instant::now();
+    let data = Arc::new((Mutex::new(false), Condvar::new()));
+    let data2 = data.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(1));
+        *data2.0.lock() = true;
+        data2.1.notify_one();
+    });
+    let mut lock = data.0.lock();
+    while !*lock {
+        assert! {!data.1.wait_for(&mut lock, Duration::from_secs(10)).timed_out()};
+    }
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(1)};
+}
+
+#[chronobreak::test(scheduled)]
+fn wait_until_is_notified() {
+    let start_time = Instant::now();
+    let data = Arc::new((Mutex::new(false), Condvar::new()));
+    let data2 = data.clone();
+    thread::spawn(move || {
+        thread::sleep(Duration::from_secs(1));
+        *data2.0.lock() = true;
+        data2.1.notify_all();
+    });
+    let deadline = start_time + Duration::from_secs(10);
+    let mut lock = data.0.lock();
+    while !*lock {
+        assert! {!data.1.wait_until(&mut lock, deadline).timed_out()};
+    }
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(1)};
+}
@@ -0,0 +1,149 @@
+use chronobreak::scenario::{Scenario, Step};
+#[chronobreak]
+mod mock {
+    pub use std::sync::{Arc, Mutex};
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+/// A server that fails over to its backup once the primary did not respond
+/// for 30 seconds.
+struct Server {
+    last_response: Instant,
+}
+
+impl Server {
+    fn primary_active(&self) -> bool {
+        self.last_response.elapsed() < Duration::from_secs(30)
+    }
+}
+
+#[chronobreak::test(scenario = "scenarios/failover.toml")]
+fn failover(scenario: &mut Scenario) {
+    let server = Arc::new(Mutex::new(Server {
+        last_response: Instant::now(),
+    }));
+    let responding = Arc::new(Mutex::new(true));
+    {
+        let server = server.clone();
+        let responding = responding.clone();
+        scenario.assertion("primary_active", move || {
+            let mut server = server.lock().unwrap();
+            if *responding.lock().unwrap() {
+                server.last_response = Instant::now();
+            }
+            server.primary_active()
+        });
+    }
+    scenario.assertion("backup_active", move || {
+        !server.lock().unwrap().primary_active()
+    });
+    scenario.hook("drop_primary", move || *responding.lock().unwrap() = false);
+}
+
+#[chronobreak::test(scenario = "scenarios/frozen.json")]
+fn frozen_steps(scenario: &mut Scenario) {
+    let sleeper = Arc::new(Mutex::new(None));
+    {
+        let sleeper = sleeper.clone();
+        scenario.hook("spawn_sleeper", move || {
+            *sleeper.lock().unwrap() = Some(thread::spawn(|| {
+                thread::sleep(Duration::from_secs(1));
+                Instant::now()
+            }));
+        });
+    }
+    let start_time = Instant::now();
+    scenario.assertion("sleeper_woke_up", move || {
+        let sleeper = sleeper.lock().unwrap().take().unwrap();
+        sleeper.join().unwrap() == start_time + Duration::from_secs(1)
+    });
+}
+
+#[chronobreak::test]
+fn steps_are_scheduled_at_their_time() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let worker = Arc::new(Mutex::new(None));
+    let mut scenario = Scenario::new(
+        "inline",
+        vec![
+            Step::Freeze,
+            Step::Hook("spawn_worker".to_string()),
+            Step::Advance(Duration::from_secs(5)),
+            Step::Hook("mark".to_string()),
+            Step::Advance(Duration::from_secs(5)),
+            Step::Unfreeze,
+            Step::Assert("marked_after_four_ticks".to_string()),
+        ],
+    );
+    {
+        let log = log.clone();
+        let worker = worker.clone();
+        scenario.hook("spawn_worker", move || {
+            let log = log.clone();
+            *worker.lock().unwrap() = Some(thread::spawn(move || {
+                for _ in 0..10 {
+                    thread::sleep(Duration::from_secs(1));
+                    log.lock().unwrap().push("tick");
+                }
+            }));
+        });
+    }
+    {
+        let log = log.clone();
+        scenario.hook("mark", move || log.lock().unwrap().push("mark"));
+    }
+    scenario.assertion("marked_after_four_ticks", move || {
+        let worker = worker.lock().unwrap().take().unwrap();
+        worker.join().unwrap();
+        let log = log.lock().unwrap();
+        log.iter().position(|entry| *entry == "mark") == Some(4) && log.len() == 11
+    });
+    scenario.run();
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "step 2 of scenario `inline` (assert `never`) failed at local time")]
+fn failed_step_is_reported() {
+    let mut scenario = Scenario::new(
+        "inline",
+        vec![
+            Step::Advance(Duration::from_secs(1)),
+            Step::Assert("never".to_string()),
+        ],
+    );
+    scenario.assertion("never", || false);
+    scenario.run();
+}
+
+#[chronobreak::test]
+#[should_panic(
+    expected = "(hook `missing`) failed at local time Some(0ns): hook is not registered"
+)]
+fn missing_hook_fails_before_running() {
+    Scenario::new(
+        "inline",
+        vec![
+            Step::Advance(Duration::from_secs(1)),
+            Step::Hook("missing".to_string()),
+        ],
+    )
+    .run();
+}
+
+#[test]
+fn invalid_scenarios_are_rejected() {
+    let err =
+        Scenario::from_toml("a.toml", "[[steps]]\nadvance = \"1s\"\nhook = \"x\"").unwrap_err();
+    assert_eq! {err.to_string(), "invalid scenario `a.toml`: step 1: step contains more than one action"};
+    let err = Scenario::from_json("b.json", r#"{"steps": [{"advance": "soon"}]}"#).unwrap_err();
+    assert_eq! {err.to_string(), "invalid scenario `b.json`: step 1: invalid duration `soon`"};
+    assert! {Scenario::parse("c.yaml", "").is_err()};
+    let scenario = Scenario::parse(
+        "d.json",
+        r#"{"steps": [{"freeze": true}, {"unfreeze": true}]}"#,
+    )
+    .unwrap();
+    assert_eq! {scenario.steps(), &[Step::Freeze, Step::Unfreeze]};
+}
@@ -0,0 +1,95 @@
+#[chronobreak]
+mod mock {
+    pub use parking_lot::{Condvar, Mutex};
+    pub use std::sync::Arc;
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+#[chronobreak::test(scheduled)]
+fn sleep_advances() {
+    let start_time = Instant::now();
+    thread::sleep(Duration::from_secs(1));
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(1)};
+}
+
+#[chronobreak::test(scheduled)]
+fn sleepers_wake_in_deadline_order() {
+    let start_time = Instant::now();
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let threads: Vec<_> = [5, 1, 3]
+        .iter()
+        .map(|&secs| {
+            let order = order.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_secs(secs));
+                order.lock().push((secs, Instant::now()));
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let expected: Vec<_> = [1, 3, 5]
+        .iter()
+        .map(|&secs| (secs, start_time + Duration::from_secs(secs)))
+        .collect();
+    assert_eq! {*order.lock(), expected};
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(5)};
+}
+
+#[chronobreak::test(scheduled)]
+fn waits_for_runnable_threads() {
+    let start_time = Instant::now();
+    let data = Arc::new((Mutex::new(false), Condvar::new()));
+    let data2 = data.clone();
+    let thread = thread::spawn(move || {
+        thread::sleep(Duration::from_secs(2));
+        *data2.0.lock() = true;
+        data2.1.notify_all();
+    });
+    let mut ready = data.0.lock();
+    while !*ready {
+        data.1.wait(&mut ready);
+    }
+    assert_eq! {Instant::now(), start_time + Duration::from_secs(2)};
+    drop(ready);
+    thread.join().unwrap();
+}
+
+#[chronobreak::test(scheduled)]
+fn mutex_holder_may_sleep() {
+    let start_time = Instant::now();
+    let mutex = Arc::new(Mutex::new(()));
+    let guard = mutex.lock();
+    let mutex2 = mutex.clone();
+    let thread = thread::spawn(move || {
+        mutex2.lock();
+        Instant::now()
+    });
+    clock::advance(Duration::from_secs(1));
+    drop(guard);
+    assert_eq! {thread.join().unwrap(), start_time + Duration::from_secs(1)};
+}
+
+#[chronobreak::test(scheduled)]
+fn scheduled_callbacks_run_between_sleeps() {
+    let events = Arc::new(::std::sync::Mutex::new(Vec::new()));
+    for secs in [30, 10] {
+        let events = events.clone();
+        clock::schedule_after(Duration::from_secs(secs), move || {
+            events.lock().unwrap().push(secs)
+        });
+    }
+    let sleeper = {
+        let events = events.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_secs(20));
+            events.lock().unwrap().push(20);
+        })
+    };
+    sleeper.join().unwrap();
+    thread::sleep(Duration::from_secs(20));
+    assert_eq! {*events.lock().unwrap(), vec![10, 20, 30]};
+}
@@ -0,0 +1,115 @@
+use chronobreak::clock::WaitKind;
+
+#[chronobreak]
+mod mock {
+    pub use futures_timer::Delay;
+    pub use parking_lot::{Condvar, Mutex};
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+fn backoff(attempts: u32) {
+    for attempt in 0..attempts {
+        thread::sleep(Duration::from_secs(1 << attempt));
+    }
+}
+
+#[chronobreak::test]
+fn spy_records_exponential_backoff() {
+    let spy = clock::spy();
+    backoff(4);
+    spy.assert_sleeps(&[
+        Duration::from_secs(1),
+        Duration::from_secs(2),
+        Duration::from_secs(4),
+        Duration::from_secs(8),
+    ]);
+}
+
+#[chronobreak::test]
+fn spy_records_caller_and_thread() {
+    let spy = clock::spy();
+    let line = line!() + 1;
+    thread::sleep(Duration::from_secs(1));
+    let waits = spy.waits();
+    assert_eq! {waits.len(), 1};
+    assert_eq! {waits[0].kind, WaitKind::Sleep};
+    assert_eq! {waits[0].location.file(), file!()};
+    assert_eq! {waits[0].location.line(), line};
+    assert_eq! {waits[0].thread, thread::current().id()};
+}
+
+#[chronobreak::test]
+fn spy_records_waits_of_other_threads() {
+    let spy = clock::spy();
+    let thread = thread::spawn(|| thread::sleep(Duration::from_secs(3)));
+    let id = thread.thread().id();
+    thread.join().unwrap();
+    let waits = spy.waits();
+    assert_eq! {waits.len(), 1};
+    assert_eq! {waits[0].thread, id};
+}
+
+#[chronobreak::test]
+async fn spy_records_delays() {
+    let spy = clock::spy();
+    let line = line!() + 1;
+    let mut delay = Delay::new(Duration::from_secs(1));
+    delay.reset(Duration::from_secs(2));
+    delay.await;
+    let waits = spy.waits();
+    assert_eq! {waits.iter().map(|wait| wait.kind).collect::<Vec<_>>(), vec![WaitKind::Delay; 2]};
+    assert_eq! {waits[0].location.line(), line};
+    assert_eq! {waits[1].location.line(), line + 1};
+    spy.assert_sleeps(&[Duration::from_secs(1), Duration::from_secs(2)]);
+}
+
+#[chronobreak::test]
+fn spy_records_timed_waits() {
+    let spy = clock::spy();
+    let line = line!() + 1;
+    thread::park_timeout(Duration::from_secs(1));
+    let mutex = Mutex::new(());
+    let condvar = Condvar::new();
+    let mut guard = mutex.lock();
+    condvar.wait_for(&mut guard, Duration::from_secs(2));
+    condvar.wait_until(&mut guard, Instant::now() + Duration::from_secs(3));
+    let waits = spy.waits();
+    assert_eq! {waits.iter().map(|wait| wait.kind).collect::<Vec<_>>(), vec![WaitKind::TimedWait; 3]};
+    assert_eq! {waits[0].location.line(), line};
+    assert_eq! {waits[1].location.line(), line + 4};
+    assert_eq! {waits[2].location.line(), line + 5};
+    spy.assert_sleeps(&[
+        Duration::from_secs(1),
+        Duration::from_secs(2),
+        Duration::from_secs(3),
+    ]);
+}
+
+#[chronobreak::test]
+fn spy_ignores_explicit_advances() {
+    let spy = clock::spy();
+    clock::advance(Duration::from_secs(1));
+    thread::sleep(Duration::from_secs(2));
+    clock::advance(Duration::from_secs(3));
+    spy.assert_sleeps(&[Duration::from_secs(2)]);
+}
+
+#[chronobreak::test]
+fn spy_only_records_while_alive() {
+    thread::sleep(Duration::from_secs(1));
+    let spy = clock::spy();
+    thread::sleep(Duration::from_secs(2));
+    spy.clear();
+    thread::sleep(Duration::from_secs(3));
+    assert_eq! {spy.durations(), vec![Duration::from_secs(3)]};
+}
+
+#[chronobreak::test]
+#[should_panic(expected = "requested waits differ\n  expected: [1s, 2s]\n  actual:   [1s, 2s, 4s]")]
+fn assert_sleeps_reports_actual_waits() {
+    let spy = clock::spy();
+    backoff(3);
+    spy.assert_sleeps(&[Duration::from_secs(1), Duration::from_secs(2)]);
+}
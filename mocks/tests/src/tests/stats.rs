@@ -0,0 +1,112 @@
+#[chronobreak]
+mod mock {
+    pub use futures_timer::Delay;
+    pub use parking_lot::{Condvar, Mutex};
+    pub use std::sync::Arc;
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+#[chronobreak::test]
+fn sleeps_and_reads_are_counted() {
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(60) {
+        thread::sleep(Duration::from_secs(20));
+    }
+    let stats = clock::stats().thread(thread::current().id());
+    assert_eq! {stats.sleeps, 3};
+    assert_eq! {stats.slept, Duration::from_secs(60)};
+    assert_eq! {stats.now_reads, 5};
+}
+
+#[chronobreak::test]
+fn stats_are_counted_per_thread() {
+    let poller = thread::spawn(|| {
+        for _ in 0..3 {
+            thread::sleep(Duration::from_secs(20));
+        }
+    });
+    let poller_id = poller.thread().id();
+    clock::advance(Duration::from_secs(5));
+    poller.join().unwrap();
+    let stats = clock::stats();
+    assert_eq! {stats.thread(poller_id).sleeps, 3};
+    assert_eq! {stats.thread(poller_id).slept, Duration::from_secs(60)};
+    assert_eq! {stats.thread(poller_id).advances, 0};
+    let main = stats.thread(thread::current().id());
+    assert_eq! {main.sleeps, 0};
+    assert_eq! {main.advances, 1};
+    assert_eq! {main.advanced, Duration::from_secs(5)};
+    assert_eq! {main.sync_advances, 1};
+    assert_eq! {stats.total().sleeps, 3};
+    assert_eq! {stats.total().advances, 1};
+    assert_eq! {stats.threads()[0].thread_index, 0};
+}
+
+#[chronobreak::test]
+fn condvar_waits_and_lock_synchronizations_are_counted() {
+    let data = Arc::new((Mutex::new(false), Condvar::new()));
+    let data2 = data.clone();
+    let thread = thread::spawn(move || {
+        thread::sleep(Duration::from_secs(1));
+        *data2.0.lock() = true;
+        data2.1.notify_all();
+    });
+    let mut ready = data.0.lock();
+    let mut waits = 0;
+    while !*ready {
+        data.1.wait(&mut ready);
+        waits += 1;
+    }
+    drop(ready);
+    thread.join().unwrap();
+    let stats = clock::stats().thread(thread::current().id());
+    assert_eq! {stats.condvar_waits, waits};
+    assert! {stats.sync_advances >= 1};
+}
+
+#[chronobreak::test]
+async fn delay_polls_are_counted() {
+    Delay::new(Duration::from_secs(1)).await;
+    let stats = clock::stats().thread(thread::current().id());
+    assert_eq! {stats.delay_polls, 1};
+}
+
+#[chronobreak::test]
+fn stats_are_printed() {
+    thread::sleep(Duration::from_secs(1));
+    let stats = clock::stats().to_string();
+    assert! {stats.contains("sleeps")};
+    assert! {stats.contains("advances")};
+    assert! {stats.contains("total")};
+}
+
+#[chronobreak::test(print_stats)]
+#[should_panic(expected = "poller woke too often")]
+fn stats_are_printed_on_failure() {
+    thread::sleep(Duration::from_secs(1));
+    panic! {"poller woke too often"};
+}
+
+#[chronobreak::test(scheduled)]
+fn timed_waits_are_counted_once_per_park() {
+    let data = Arc::new((Mutex::new(false), Condvar::new()));
+    let data2 = data.clone();
+    let notifier = thread::spawn(move || {
+        thread::sleep(Duration::from_secs(1));
+        *data2.0.lock() = true;
+        data2.1.notify_one();
+    });
+    thread::park_timeout(Duration::from_millis(500));
+    let mut lock = data.0.lock();
+    while !*lock {
+        data.1.wait_for(&mut lock, Duration::from_secs(10));
+    }
+    drop(lock);
+    notifier.join().unwrap();
+    let stats = clock::stats().thread(thread::current().id());
+    assert_eq! {stats.timed_waits, 2};
+    assert_eq! {stats.condvar_waits, 1};
+    assert_eq! {stats.delay_polls, 0};
+}
@@ -24,6 +24,25 @@ fn sleep_advances() {
     assert_eq!(Instant::now(), start_time + Duration::from_nanos(1));
 }
 
+#[chronobreak::test]
+fn park_timeout_advances() {
+    let start_time = Instant::now();
+    thread::park_timeout(Duration::from_secs(1));
+    assert_eq!(Instant::now(), start_time + Duration::from_secs(1));
+}
+
+#[chronobreak::test(scheduled)]
+fn unpark_ends_park_timeout() {
+    let start_time = Instant::now();
+    let parked = thread::spawn(move || {
+        thread::park_timeout(Duration::from_secs(10));
+        Instant::now()
+    });
+    thread::sleep(Duration::from_secs(1));
+    parked.thread().unpark();
+    assert_eq!(parked.join().unwrap(), start_time + Duration::from_secs(1));
+}
+
 #[chronobreak::test]
 fn mocked_thread_join_syncs() {
     mocked_thread_join_syncs_impl()
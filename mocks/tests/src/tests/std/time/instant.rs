@@ -124,7 +124,7 @@ fn checked_add() {
     matches! {instant, Some(_)};
 }
 
-#[chronobreak::test]
+#[chronobreak::test(instant_start = "0s")]
 fn checked_add_secs_overflow() {
     let dur = Duration::from_secs(u64::MAX);
     let instant = Instant::now().checked_add(dur);
@@ -133,7 +133,7 @@ fn checked_add_secs_overflow() {
     assert_eq! {instant, None};
 }
 
-#[chronobreak::test]
+#[chronobreak::test(instant_start = "0s")]
 fn checked_add_nanos_overflow() {
     let dur = Duration::from_secs(u64::MAX)
         + Duration::from_nanos(Duration::from_secs(1).as_nanos() as u64 - 1);
@@ -151,10 +151,33 @@ fn checked_sub() {
     matches! {instant, Some(_)};
 }
 
-#[chronobreak::test]
+#[chronobreak::test(instant_start = "0s")]
 fn checked_sub_underflow() {
     let instant = Instant::now().checked_sub(Duration::from_secs(1));
     assert_eq! {instant, None};
     let instant = Instant::now().checked_sub(Duration::from_nanos(1));
     assert_eq! {instant, None};
 }
+
+#[chronobreak::test]
+fn sub_within_instant_start() {
+    let now = Instant::now();
+    let long_ago = now - Duration::from_secs(60);
+    assert_eq! {now.duration_since(long_ago), Duration::from_secs(60)};
+    let instant = now.checked_sub(clock::DEFAULT_INSTANT_START);
+    assert! {instant.is_some()};
+    let instant = instant.unwrap().checked_sub(Duration::from_nanos(1));
+    assert_eq! {instant, None};
+}
+
+#[test]
+fn instant_start_is_configurable() {
+    let _clock = clock::mock_with(clock::Config {
+        instant_start: Duration::from_secs(1),
+        ..Default::default()
+    });
+    let instant = Instant::now().checked_sub(Duration::from_secs(1));
+    assert! {instant.is_some()};
+    let instant = instant.unwrap().checked_sub(Duration::from_nanos(1));
+    assert_eq! {instant, None};
+}
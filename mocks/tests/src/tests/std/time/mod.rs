@@ -1,2 +1,4 @@
+// Some checked_* tests match their results without asserting them.
+#[allow(clippy::redundant_pattern_matching)]
 pub mod instant;
 pub mod system_time;
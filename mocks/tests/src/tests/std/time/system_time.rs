@@ -36,3 +36,95 @@ fn elapsed() {
 fn elapsed_unix_epoch() {
     assert_eq! {SystemTime::UNIX_EPOCH.elapsed().unwrap(), Duration::default()};
 }
+
+#[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+fn start() {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert_eq! {since_epoch, Duration::from_secs(1_709_251_199)};
+}
+
+#[chronobreak::test(start = "2024-02-29T23:59:59.25+01:00")]
+fn start_with_fraction_and_offset() {
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert_eq! {since_epoch, Duration::from_millis(1_709_247_599_250)};
+}
+
+#[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+fn start_advances() {
+    clock::advance(Duration::from_secs(1));
+    let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    assert_eq! {since_epoch, Duration::from_secs(1_709_251_200)};
+}
+
+#[test]
+fn mock_with_system_time_start() {
+    let _clock = clock::mock_with(clock::Config {
+        system_time_start: Duration::from_secs(86_400),
+        ..Default::default()
+    });
+    let start = Instant::now();
+    assert_eq! {SystemTime::UNIX_EPOCH.elapsed().unwrap(), Duration::from_secs(86_400)};
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {start.elapsed(), Duration::from_secs(1)};
+}
+
+#[chronobreak::test]
+fn set_system_time() {
+    let start = Instant::now();
+    clock::set_system_time(Duration::from_secs(42));
+    assert_eq! {UNIX_EPOCH.elapsed().unwrap(), Duration::from_secs(42)};
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {UNIX_EPOCH.elapsed().unwrap(), Duration::from_secs(43)};
+    assert_eq! {start.elapsed(), Duration::from_secs(1)};
+}
+
+#[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+fn step_forward() {
+    let start = SystemTime::now();
+    clock::step_system_time_forward(Duration::from_secs(10));
+    assert_eq! {start.elapsed().unwrap(), Duration::from_secs(10)};
+}
+
+#[chronobreak::test(start = "2024-02-29T23:59:59Z")]
+fn step_backward() {
+    let start = SystemTime::now();
+    let start_instant = Instant::now();
+    clock::step_system_time_backward(Duration::from_secs(10));
+    assert_eq! {start.elapsed().unwrap_err().duration(), Duration::from_secs(10)};
+    assert_eq! {
+        start.duration_since(SystemTime::now()).unwrap(),
+        Duration::from_secs(10)
+    };
+    assert_eq! {
+        SystemTime::now().duration_since(start).unwrap_err().duration(),
+        Duration::from_secs(10)
+    };
+    assert_eq! {Instant::now(), start_instant};
+}
+
+#[chronobreak::test]
+#[should_panic]
+fn step_backward_before_unix_epoch() {
+    clock::step_system_time_backward(Duration::from_nanos(1));
+}
+
+#[chronobreak::test]
+fn slew() {
+    let start = SystemTime::now();
+    clock::advance(Duration::from_secs(1));
+    clock::slew_system_time(500_000);
+    clock::advance(Duration::from_secs(2));
+    assert_eq! {start.elapsed().unwrap(), Duration::from_secs(4)};
+    clock::slew_system_time(-500_000);
+    clock::advance(Duration::from_secs(2));
+    assert_eq! {start.elapsed().unwrap(), Duration::from_secs(5)};
+    clock::slew_system_time(0);
+    clock::advance(Duration::from_secs(2));
+    assert_eq! {start.elapsed().unwrap(), Duration::from_secs(7)};
+}
+
+#[chronobreak::test]
+#[should_panic]
+fn slew_backwards() {
+    clock::slew_system_time(-1_000_000);
+}
@@ -0,0 +1,343 @@
+use chronobreak::clock::{Timepoint, Trace, TraceEventKind};
+use chronobreak::scenario::{Scenario, Step};
+
+#[chronobreak]
+mod mock {
+    pub use parking_lot::Mutex;
+    pub use std::sync::Arc;
+    pub use std::thread;
+    pub use std::time::*;
+}
+use mock::*;
+
+fn kinds(trace: &Trace) -> Vec<TraceEventKind> {
+    trace.events().iter().map(|event| event.kind).collect()
+}
+
+#[chronobreak::test]
+fn sleeps_are_traced() {
+    clock::start_trace();
+    thread::sleep(Duration::from_secs(1));
+    thread::sleep(Duration::from_secs(2));
+    let trace = clock::stop_trace();
+    assert_eq! {kinds(&trace), vec![
+        TraceEventKind::Sleep { to: Timepoint::START + Duration::from_secs(1) },
+        TraceEventKind::Sleep { to: Timepoint::START + Duration::from_secs(3) },
+    ]};
+    let event = &trace.events()[1];
+    assert_eq! {event.thread, Some(thread::current().id())};
+    assert_eq! {event.thread_index, 0};
+    assert_eq! {event.local_time, Timepoint::START + Duration::from_secs(1)};
+}
+
+#[chronobreak::test]
+fn events_are_only_recorded_while_tracing() {
+    clock::advance(Duration::from_secs(1));
+    clock::start_trace();
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {clock::trace().events().len(), 1};
+    let trace = clock::stop_trace();
+    clock::advance(Duration::from_secs(1));
+    assert_eq! {trace.events().len(), 1};
+    assert! {clock::stop_trace().events().is_empty()};
+}
+
+#[chronobreak::test]
+fn join_and_mutex_synchronization_is_traced() {
+    clock::start_trace();
+    let mutex = Arc::new(Mutex::new(()));
+    let mutex2 = mutex.clone();
+    let sleeper = thread::spawn(move || {
+        let _guard = mutex2.lock();
+        thread::sleep(Duration::from_secs(1));
+    });
+    let sleeper_id = sleeper.thread().id();
+    sleeper.join().unwrap();
+    drop(mutex.lock());
+    let trace = clock::stop_trace();
+    let sleeper = trace
+        .events()
+        .iter()
+        .find(|event| event.thread == Some(sleeper_id))
+        .unwrap();
+    assert_eq! {sleeper.kind, TraceEventKind::Sleep { to: Timepoint::START + Duration::from_secs(1) }};
+    let synced: Vec<_> = trace
+        .events()
+        .iter()
+        .filter(|event| event.thread == Some(thread::current().id()))
+        .map(|event| event.kind)
+        .collect();
+    assert_eq! {synced, vec![TraceEventKind::Sync { to: Timepoint::START + Duration::from_secs(1) }]};
+}
+
+#[chronobreak::test(scheduled)]
+fn delay_future_registration_and_wake_up_are_traced() {
+    clock::start_trace();
+    clock::block_on(clock::DelayFuture::new(Duration::from_secs(2)));
+    let deadline = Timepoint::START + Duration::from_secs(2);
+    assert_eq! {kinds(&clock::stop_trace()), vec![
+        TraceEventKind::TimerRegistered { deadline },
+        TraceEventKind::TimerFired { deadline },
+    ]};
+}
+
+#[chronobreak::test]
+fn freeze_transitions_are_traced() {
+    clock::start_trace();
+    Scenario::new("freeze", vec![Step::Freeze, Step::Freeze, Step::Unfreeze]).run();
+    assert_eq! {kinds(&clock::stop_trace()), vec![TraceEventKind::Freeze, TraceEventKind::Unfreeze]};
+}
+
+#[chronobreak::test]
+fn trace_is_exported_as_chrome_json() {
+    clock::start_trace();
+    thread::spawn(|| thread::sleep(Duration::from_millis(1500)))
+        .join()
+        .unwrap();
+    let json: serde_json::Value =
+        serde_json::from_str(&clock::stop_trace().to_chrome_json()).unwrap();
+    assert_eq! {json["displayTimeUnit"], "ms"};
+    let events = json["traceEvents"].as_array().unwrap();
+    assert! {events.iter().any(|event| event["ph"] == "M")};
+    let sleep = events
+        .iter()
+        .find(|event| event["name"] == "sleep")
+        .unwrap();
+    assert_eq! {sleep["cat"], "clock"};
+    assert_eq! {sleep["ph"], "X"};
+    assert_eq! {sleep["ts"], 0.0};
+    assert_eq! {sleep["dur"], 1_500_000.0};
+    let sync = events.iter().find(|event| event["name"] == "sync").unwrap();
+    assert_eq! {sync["ph"], "X"};
+    assert_eq! {sync["ts"], 0.0};
+}
+
+#[chronobreak::test]
+fn sleeps_and_advances_are_distinguished() {
+    clock::start_trace();
+    thread::sleep(Duration::from_secs(1));
+    clock::advance(Duration::from_secs(2));
+    assert_eq! {kinds(&clock::stop_trace()), vec![
+        TraceEventKind::Sleep { to: Timepoint::START + Duration::from_secs(1) },
+        TraceEventKind::Advance { to: Timepoint::START + Duration::from_secs(3) },
+    ]};
+}
+
+#[chronobreak::test]
+fn timed_waits_are_traced() {
+    clock::start_trace();
+    thread::park_timeout(Duration::from_secs(1));
+    assert_eq! {kinds(&clock::stop_trace()), vec![
+        TraceEventKind::TimedWait { deadline: Timepoint::START + Duration::from_secs(1) },
+    ]};
+}
+
+#[chronobreak::test]
+fn trace_is_parsed_from_chrome_json() {
+    clock::start_trace();
+    thread::spawn(|| thread::sleep(Duration::from_millis(1500)))
+        .join()
+        .unwrap();
+    clock::advance(Duration::from_secs(1));
+    thread::park_timeout(Duration::from_secs(1));
+    let trace = clock::stop_trace();
+    let parsed = Trace::from_chrome_json(&trace.to_chrome_json()).unwrap();
+    let mut expected = trace.events().to_vec();
+    for event in &mut expected {
+        event.thread = None;
+    }
+    assert_eq! {parsed.events(), &expected[..]};
+}
+
+#[chronobreak::test]
+fn pretty_printed_trace_is_parsed() {
+    clock::start_trace();
+    thread::spawn(|| thread::sleep(Duration::from_micros(1_500_001)))
+        .join()
+        .unwrap();
+    let trace = clock::stop_trace();
+    let json: serde_json::Value = serde_json::from_str(&trace.to_chrome_json()).unwrap();
+    let pretty = serde_json::to_string_pretty(&json).unwrap();
+    let parsed = Trace::from_chrome_json(&pretty).unwrap();
+    let mut expected = trace.events().to_vec();
+    for event in &mut expected {
+        event.thread = None;
+    }
+    assert_eq! {parsed.events(), &expected[..]};
+}
+
+#[test]
+fn unsupported_traces_are_rejected() {
+    assert! {Trace::from_chrome_json("{").unwrap_err().starts_with("invalid JSON: ")};
+    assert_eq! {
+        Trace::from_chrome_json("[]").unwrap_err(),
+        "missing or invalid field `traceEvents`"
+    };
+    assert_eq! {
+        Trace::from_chrome_json(r#"{"traceEvents": [{"name": "x", "ph": "B", "tid": 0}]}"#)
+            .unwrap_err(),
+        "trace event 1: unsupported trace event"
+    };
+    let json = r#"{"traceEvents": [
+        {"name": "thread_name", "ph": "M", "pid": 1, "tid": 0, "args": {"name": "main"}},
+        {"name": "advance", "cat": "clock", "ph": "X", "tid": 0, "args": {"to_ns": "1"}}
+    ]}"#;
+    assert_eq! {
+        Trace::from_chrome_json(json).unwrap_err(),
+        "trace event 2: missing or invalid field `args.to_ns`"
+    };
+    let json = r#"{"traceEvents": [{"name": "nap", "cat": "clock", "ph": "i", "tid": 0}]}"#;
+    assert_eq! {
+        Trace::from_chrome_json(json).unwrap_err(),
+        "trace event 1: unknown event `nap`"
+    };
+}
+
+thread_local! {
+    static WORKER: ::std::cell::Cell<&'static str> = const { ::std::cell::Cell::new("main") };
+}
+
+/// Spawns two threads that sleep for one and two seconds and returns the
+/// thread that ran a callback scheduled at one second. If `sequenced`, the
+/// first thread sleeps first, otherwise it only sleeps after a real delay.
+fn callback_thread(sequenced: bool) -> &'static str {
+    let (sender, receiver) = ::std::sync::mpsc::channel();
+    let callback_thread = Arc::new(Mutex::new(""));
+    {
+        let callback_thread = callback_thread.clone();
+        let time = chronobreak::clock::get() + Duration::from_secs(1);
+        chronobreak::clock::schedule_at(time, move || {
+            *callback_thread.lock() = WORKER.with(|worker| worker.get());
+        });
+    }
+    let first = thread::spawn(move || {
+        WORKER.with(|worker| worker.set("first"));
+        if !sequenced {
+            ::std::thread::sleep(::std::time::Duration::from_millis(50));
+        }
+        thread::sleep(Duration::from_secs(1));
+        let _ = sender.send(());
+    });
+    let second = thread::spawn(move || {
+        WORKER.with(|worker| worker.set("second"));
+        if sequenced {
+            receiver.recv().unwrap();
+        }
+        thread::sleep(Duration::from_secs(2));
+    });
+    first.join().unwrap();
+    second.join().unwrap();
+    let worker = *callback_thread.lock();
+    worker
+}
+
+/// Spawns two threads that each sleep twice and note their index in between.
+/// If `sequenced`, the second thread runs first.
+fn sleepers(sequenced: bool) -> Vec<usize> {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let (sender, receiver) = ::std::sync::mpsc::channel();
+    let order1 = order.clone();
+    let first = thread::spawn(move || {
+        if sequenced {
+            receiver.recv().unwrap();
+        }
+        thread::sleep(Duration::from_secs(1));
+        order1.lock().push(1);
+        thread::sleep(Duration::from_secs(1));
+    });
+    let order2 = order.clone();
+    let second = thread::spawn(move || {
+        thread::sleep(Duration::from_secs(1));
+        order2.lock().push(2);
+        thread::sleep(Duration::from_secs(1));
+        let _ = sender.send(());
+    });
+    first.join().unwrap();
+    second.join().unwrap();
+    let order = order.lock().clone();
+    order
+}
+
+#[test]
+fn replay_forces_recorded_order() {
+    let trace = {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::start_trace();
+        assert_eq! {sleepers(true), vec![2, 1]};
+        chronobreak::clock::stop_trace()
+    };
+    for _ in 0..10 {
+        let _clock = chronobreak::clock::mock();
+        assert_eq! {chronobreak::clock::replay(&trace, || sleepers(false)), vec![2, 1]};
+    }
+}
+
+#[test]
+fn replay_orders_side_effects() {
+    let trace = {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::start_trace();
+        assert_eq! {callback_thread(true), "first"};
+        chronobreak::clock::stop_trace()
+    };
+    let _clock = chronobreak::clock::mock();
+    assert_eq! {chronobreak::clock::replay(&trace, || callback_thread(false)), "first"};
+}
+
+#[test]
+fn replay_of_parsed_trace() {
+    let json = {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::start_trace();
+        sleepers(true);
+        chronobreak::clock::stop_trace().to_chrome_json()
+    };
+    let trace = Trace::from_chrome_json(&json).unwrap();
+    let _clock = chronobreak::clock::mock();
+    assert_eq! {chronobreak::clock::replay(&trace, || sleepers(false)), vec![2, 1]};
+}
+
+#[test]
+#[should_panic(
+    expected = "chronobreak replay diverged: event 2 diverged from the recorded trace: expected thread #0"
+)]
+fn replay_reports_first_divergence() {
+    let trace = {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::start_trace();
+        thread::sleep(Duration::from_secs(1));
+        thread::sleep(Duration::from_secs(1));
+        chronobreak::clock::stop_trace()
+    };
+    let _clock = chronobreak::clock::mock();
+    chronobreak::clock::replay(&trace, || {
+        thread::sleep(Duration::from_secs(1));
+        thread::sleep(Duration::from_secs(2));
+    });
+}
+
+#[test]
+#[should_panic(expected = "the run ended after 1 of 2 recorded events")]
+fn replay_reports_missing_events() {
+    let trace = {
+        let _clock = chronobreak::clock::mock();
+        chronobreak::clock::start_trace();
+        thread::sleep(Duration::from_secs(1));
+        thread::sleep(Duration::from_secs(1));
+        chronobreak::clock::stop_trace()
+    };
+    let _clock = chronobreak::clock::mock();
+    chronobreak::clock::replay(&trace, || thread::sleep(Duration::from_secs(1)));
+}
+
+#[test]
+fn replay_on_scheduled_clock() {
+    let trace = {
+        let _clock = chronobreak::clock::scheduled();
+        chronobreak::clock::start_trace();
+        sleepers(false);
+        chronobreak::clock::stop_trace()
+    };
+    let _clock = chronobreak::clock::scheduled();
+    chronobreak::clock::replay(&trace, || sleepers(false));
+}